@@ -10,8 +10,12 @@
 //!
 //! *   **`#[sys_function]`**: Automatically dispatches method calls to platform-specific implementations (e.g., `fn run()` calls `Self::run_impl()`).
 //! *   **`#[sys_trait_function]`**: Applies platform configuration to trait method definitions.
-//! *   **`#[sys_struct]`**: Generates platform-specific type aliases (e.g., `MyStruct` -> `MyStructLinux`) and optionally enforces trait bounds (e.g., `Send + Sync`) at compile time.
+//! *   **`#[sys_struct]`**: Generates platform-specific type aliases for structs, enums and unions (e.g., `MyStruct` -> `MyStructLinux`) and optionally enforces trait bounds (e.g., `Send + Sync`) at compile time.
 //! *   **`#[platform_mod]`**: Declares platform-dependent modules backed by OS-specific files, with strict visibility control.
+//! *   **`sys_type!`**: Declares type aliases whose definition is selected per platform (e.g., `i32` on POSIX, `*mut c_void` on Windows).
+//! *   **`#[sys_const]` / `#[sys_static]`**: Declares constants and statics whose value is selected per platform.
+//! *   **`select!`**: Selects an expression per platform, like a `match` evaluated at compile time.
+//! *   **`supported_platforms!`**: Turns builds for unsupported targets into a single clear compile error.
 //! *   **Flexible Logic**: Supports explicit inclusion (`include`) and exclusion (`exclude`) of platforms.
 //! *   **Platform Groups**: Includes helper keywords like `posix` (Linux + macOS) or `all`.
 //!
@@ -32,6 +36,86 @@
 //! 2. Remove any platforms specified in the `exclude` list.
 //! 3. Generate the corresponding `#[cfg(any(...))]` attributes.
 //!
+//! The compiler evaluates `#[cfg(...)]` attributes of the item itself before the macro runs, so an item
+//! marked `#[cfg(feature = "x")]` and `include(linux)` exists where `all(feature = "x", target_os = "linux")`
+//! holds, together with everything generated from it. Nested `#[cfg(...)]`s the macro does see (on the platform
+//! blocks of inline [`platform_mod`] modules) are merged into a single `#[cfg(all(...))]` attribute.
+//!
+//! Generated predicates are normalised: nested `all`/`any` are flattened, duplicate and redundant terms are
+//! removed, single terms are unwrapped and the terms are sorted, so the same configuration always produces
+//! the same `#[cfg]`. For example, a block marked `#[cfg(all(unix))] #[linux]` gets
+//! `#[cfg(all(unix, target_os = "linux"))]`. `true` and `false` are folded away, and predicates the normalisation
+//! does not understand (e.g., `version("1.80")`) are kept as written.
+//!
+//! ## Open and Closed World
+//!
+//! By default, the platform set is a *closed world*: `all` means exactly Linux, macOS and Windows, so
+//! `exclude(windows)` compiles to `#[cfg(any(target_os = "linux", target_os = "macos"))]` and the item
+//! vanishes on FreeBSD. With `world = open`, platforms other than the listed ones belong to `all` as well,
+//! and configurations without a specific `include(...)` compile to complements:
+//!
+//! ```rust
+//! # use platify::sys_function;
+//! # struct Terminal;
+//! impl Terminal {
+//!     // #[cfg(not(target_os = "windows"))]: also available on FreeBSD, Android, ...
+//!     #[sys_function(exclude(windows), world = open)]
+//!     fn set_raw_mode(&self);
+//! }
+//! # impl Terminal { fn set_raw_mode_impl(&self) {} }
+//! ```
+//!
+//! Without any exclusion, the item is not restricted at all. In tables (`select!`, `sys_type!`,
+//! `#[sys_const]`, `#[sys_static]`), the `_` arm covers the other platforms. `world = closed` is the default.
+//! There is intentionally no crate-wide switch: a Cargo feature would also change the meaning of the
+//! attributes in every other crate of the build. [`platform_mod`] always uses the closed world, as platform
+//! modules only exist for the listed platforms.
+//!
+//! ## Lints
+//!
+//! Configurations that are redundant or contradictory are reported as warnings: platforms listed twice
+//! (`include(linux, linux)`), platforms already covered by a group (`include(posix, linux)`), excluded
+//! platforms that were never included (`include(linux), exclude(windows)`) and an explicit `include(all)`.
+//! Add the `strict` option (e.g., `#[sys_function(include(linux), strict)]`) to turn them into errors.
+//!
+//! Trait methods without a default body have no place for the warnings, so they are only reported
+//! in `strict` mode there.
+//!
+//! ## Documentation on docs.rs
+//!
+//! - `doc_cfg`: Adds `#[cfg_attr(docsrs, doc(cfg(...)))]` with the computed predicate to the generated items, so
+//!   docs.rs shows a platform badge. Requires `#![cfg_attr(docsrs, feature(doc_cfg))]` in your crate root.
+//! - `docs_all_platforms`: Also compiles the items when building documentation (`cfg(doc)`), so a single
+//!   `cargo doc` covers the API of every platform. `#[sys_function]` wrappers get a stub body on platforms
+//!   without the `_impl` method, and so do `#[sys_trait_function]` methods without a default body, which
+//!   implementations on those platforms do not provide. For `#[platform_mod]`, see its documentation.
+//!
+//! ```rust
+//! # use platify::sys_function;
+//! # struct Console;
+//! impl Console {
+//!     #[sys_function(include(windows), doc_cfg, docs_all_platforms)]
+//!     pub fn enable_ansi(&self);
+//! #   #[cfg(windows)]
+//! #   fn enable_ansi_impl(&self) {}
+//! }
+//! ```
+//!
+//! ## Explaining the Platform Set
+//!
+//! Add the `explain` option to any macro to see which `cfg` it generated. It emits a warning listing the
+//! `include` and `exclude` sets, their expansion, the final platforms and the generated `#[cfg(...)]`
+//! attributes, and appends a line such as "Available on: Linux, macOS" to the documentation of the generated
+//! items:
+//!
+//! ```text
+//! warning: use of deprecated unit struct `_::platify`: explain: include(posix) -> {linux, macos},
+//!          exclude(macos) -> {macos}, platforms {linux}, generated #[cfg(target_os = "linux")]
+//! ```
+//!
+//! The warning is emitted inside the generated item, so on [`sys_trait_function`] `explain` needs a method
+//! with a default body.
+//!
 //! ---
 //!
 //! ## Examples
@@ -145,16 +229,25 @@
 //! use my_crate::linux::Device;
 //! ```
 
+#![allow(clippy::needless_borrowed_reference)]
+
 use proc_macro::TokenStream;
 use proc_macro2::{Span as Span2, TokenStream as TokenStream2};
-use quote::{format_ident, quote, ToTokens as _};
+use quote::{format_ident, quote, quote_spanned, ToTokens as _};
+use std::collections::BTreeMap;
 use std::collections::{BTreeSet, HashSet};
-use syn::parse::{Parse, ParseStream};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::{env, fmt, fs, mem};
+use syn::ext::IdentExt as _;
+use syn::parse::{Lookahead1, Parse, ParseStream, Parser as _};
+use syn::punctuated::Punctuated;
 use syn::spanned::Spanned as _;
 use syn::{
-    parenthesized, parse, parse_macro_input, token, Attribute, ConstParam, Error, FnArg,
-    ForeignItemFn, GenericParam, ItemFn, ItemMod, ItemStruct, ItemUse, Pat, PatType, ReturnType,
-    Signature, TraitItemFn, TypeParam, UseTree, Visibility,
+    braced, parenthesized, parse, parse_macro_input, parse_quote, token, Attribute, ConstParam,
+    Error, Expr, FnArg, ForeignItemFn, GenericParam, Generics, ImplItem, Item, ItemFn, ItemMod,
+    ItemUse, LitBool, LitStr, Meta, Pat, PatType, ReturnType, Signature, Stmt, Token, TraitItemFn,
+    Type, TypeParam, UsePath, UseTree, Visibility,
 };
 
 /// Applies platform configuration to trait method definitions.
@@ -165,16 +258,61 @@ use syn::{
 ///
 /// - `include(...)`: Whitelist of platforms. Options: `linux`, `macos`, `windows`, `all`, `posix`.
 /// - `exclude(...)`: Blacklist of platforms. Removes them from the included set.
+///
+/// The [lint](crate#lints) warnings are reported inside the default body. Methods without one
+/// silently drop them, unless `strict` turns them into errors.
 #[proc_macro_attribute]
 pub fn sys_trait_function(attr: TokenStream, item: TokenStream) -> TokenStream {
     let attr = parse_macro_input!(attr as AttrOptions);
-    let cfg_attr = attr.convert_to_cfg_attr();
 
-    let trait_fn = parse_macro_input!(item as TraitItemFn);
+    let mut trait_fn = parse_macro_input!(item as TraitItemFn);
+    let (warnings, doc) = attr.explain_item();
+    trait_fn.attrs.extend(doc);
+
+    // With `docs_all_platforms`, a required method compiled for documentation would also be required
+    // from the implementations of other platforms, so they get a stub with a default body instead.
+    let (cfg_attr, stub) = if trait_fn.default.is_some() {
+        (attr.convert_to_cfg_attr(), None)
+    } else {
+        let predicate = attr.predicate(
+            &attr.allowed_set(|platform| platform),
+            attr.includes_other_platforms(),
+        );
+        let stub = attr.doc_stub_cfg_attrs(&predicate).map(|stub_cfg_attrs| {
+            let attrs = &trait_fn.attrs;
+            let sig = &trait_fn.sig;
+            quote! {
+                #stub_cfg_attrs
+                #(#attrs)*
+                #[allow(unused_variables)]
+                #sig {
+                    ::core::unimplemented!()
+                }
+            }
+        });
+        (attr.convert_to_platform_cfg_attr(), stub)
+    };
+    // Without a default body there is no place for the warnings; `strict` still reports the lints.
+    let error = match (trait_fn.default.as_mut(), attr.explain) {
+        (Some(block), _) => {
+            block.stmts.insert(0, Stmt::Item(Item::Verbatim(warnings)));
+            None
+        }
+        (None, Some(span)) => Some(
+            Error::new(
+                span,
+                "`explain` needs a default body on trait methods: the warning is reported inside it",
+            )
+            .to_compile_error(),
+        ),
+        (None, None) => None,
+    };
 
     quote! {
+        #error
         #cfg_attr
         #trait_fn
+        #stub
     }
     .into()
 }
@@ -189,6 +327,8 @@ pub fn sys_trait_function(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///
 /// - `include(...)`: Whitelist of platforms. Options: `linux`, `macos`, `windows`, `all`, `posix`.
 /// - `exclude(...)`: Blacklist of platforms. Removes them from the included set.
+/// - `world = open | closed`: Whether `all` also covers platforms other than Linux, macOS and Windows
+///   (see the crate documentation). Defaults to `closed`.
 ///
 /// If `include` is omitted, it defaults to `all` (minus any exclusions).
 ///
@@ -198,6 +338,18 @@ pub fn sys_trait_function(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// 2. Applies `#[cfg(any(target_os = "..."))]` to the method.
 /// 3. Generates a default implementation: `fn foo(&self) { Self::foo_impl(self) }`.
 ///
+/// # Attributes
+///
+/// Attributes of the declaration are handled as follows:
+///
+/// - `#[cfg(...)]` is evaluated by the compiler before the macro runs: the declaration disappears
+///   where it does not hold, so it effectively combines with the generated `#[cfg]` via `all(...)`.
+/// - `#[no_mangle]`, `#[export_name]`, `#[link_section]`, `#[test]` and similar attributes bind a
+///   function to a symbol or a harness and are rejected; put them on the `_impl` method instead.
+/// - Everything else (`#[doc]`, lint levels, `#[inline]`, `#[must_use]`, `#[track_caller]`, ...) is
+///   forwarded to the generated method. Mark the `_impl` method `#[track_caller]` as well to have
+///   its panics point at the caller.
+///
 /// # Requirements
 ///
 /// The implementing type must define the corresponding `_impl` method.
@@ -205,12 +357,19 @@ pub fn sys_trait_function(attr: TokenStream, item: TokenStream) -> TokenStream {
 pub fn sys_function(attr: TokenStream, item: TokenStream) -> TokenStream {
     let attr = parse_macro_input!(attr as AttrOptions);
     let cfg_attr = attr.convert_to_cfg_attr();
+    let (warnings, doc) = attr.explain_item();
 
     let struct_info = match parse::<ForeignItemFn>(item.clone()) {
         Ok(foreign_item_fn) => foreign_item_fn,
         Err(_) => {
             return match parse::<ItemFn>(item) {
-                Ok(item_fn) => {
+                Ok(mut item_fn) => {
+                    item_fn.attrs.extend(doc);
+                    item_fn
+                        .block
+                        .stmts
+                        .insert(0, Stmt::Item(Item::Verbatim(warnings)));
+
                     quote! {
                         #cfg_attr
                         #item_fn
@@ -244,6 +403,12 @@ pub fn sys_function(attr: TokenStream, item: TokenStream) -> TokenStream {
     } = &sig;
 
     let sys_ident = format_ident!("{ident}_impl");
+
+    let attr_errors = attrs
+        .iter()
+        .filter_map(|fn_attr| forwarder_attr_error(fn_attr, &sys_ident))
+        .map(|err| err.to_compile_error())
+        .collect::<TokenStream2>();
     let asyncness = asyncness
         .as_ref()
         .map_or_else(TokenStream2::new, |_| quote!(.await));
@@ -288,12 +453,33 @@ pub fn sys_function(attr: TokenStream, item: TokenStream) -> TokenStream {
         body = quote!(unsafe { #body });
     }
 
+    // With `docs_all_platforms`, other platforms get a stub, as the `_impl` method does not exist there.
+    let predicate = attr.predicate(
+        &attr.allowed_set(|platform| platform),
+        attr.includes_other_platforms(),
+    );
+    let stub = attr.doc_stub_cfg_attrs(&predicate).map(|stub_cfg_attrs| {
+        quote! {
+            #stub_cfg_attrs
+            #(#attrs)*
+            #(#doc)*
+            #[allow(unused_variables)]
+            #vis #sig {
+                ::core::unimplemented!()
+            }
+        }
+    });
+    let cfg_attr = attr.convert_to_platform_cfg_attr();
+
     let result = quote! {
         #cfg_attr
         #(#attrs)*
+        #(#doc)*
         #vis #sig {
+            #warnings
             #body
         }
+        #stub
     };
 
     let variadic_error = variadic
@@ -305,20 +491,65 @@ pub fn sys_function(attr: TokenStream, item: TokenStream) -> TokenStream {
 
     quote! {
         #result
+        #attr_errors
         #param_errors
         #variadic_error
     }
     .into()
 }
 
-/// Generates platform-specific type aliases for a struct.
+/// Attributes that bind a function to a symbol, a section or a test harness. They make no sense on
+/// the forwarder generated by `#[sys_function]`, which only exists to call the `_impl` method.
+const FORWARDER_FORBIDDEN_ATTRS: &[&str] = &[
+    "bench",
+    "export_name",
+    "ignore",
+    "link_name",
+    "link_section",
+    "naked",
+    "no_mangle",
+    "proc_macro",
+    "proc_macro_attribute",
+    "proc_macro_derive",
+    "should_panic",
+    "test",
+    "used",
+];
+
+/// Returns an error if `attr` must not be placed on the forwarder of a `#[sys_function]`.
+#[must_use]
+fn forwarder_attr_error(attr: &Attribute, sys_ident: &syn::Ident) -> Option<Error> {
+    // Edition 2024 spells some of them as `#[unsafe(no_mangle)]`.
+    let name = if attr.path().is_ident("unsafe") {
+        attr.parse_args::<Meta>()
+            .ok()?
+            .path()
+            .get_ident()?
+            .to_string()
+    } else {
+        attr.path().get_ident()?.to_string()
+    };
+
+    FORWARDER_FORBIDDEN_ATTRS.contains(&name.as_str()).then(|| {
+        Error::new(
+            attr.span(),
+            format!(
+                "`#[{name}]` cannot be used with #[sys_function]: the generated method only forwards to `{sys_ident}`"
+            ),
+        )
+    })
+}
+
+/// Generates platform-specific type aliases for a struct, enum or union.
 ///
-/// It preserves the original struct definition and adds type aliases that are only available
-/// on specific platforms.
+/// It preserves the original type definition and adds type aliases that are only available
+/// on specific platforms (e.g., `Handle` -> `HandleLinux`, `HandleWindows`).
 ///
 /// # Options
 ///
 /// - `traits(...)`: Comma-separated list of traits (e.g., `Send, Sync`) to assert at compile time.
+///   A plain trait is checked for *all* valid generic arguments. Use `Trait for Type` (e.g.,
+///   `Send for Handle<u8>`) to assert the bound only for a specific instantiation instead.
 /// - `include(...)`: Whitelist of platforms.
 /// - `exclude(...)`: Blacklist of platforms.
 ///
@@ -327,22 +558,111 @@ pub fn sys_function(attr: TokenStream, item: TokenStream) -> TokenStream {
 pub fn sys_struct(attr: TokenStream, item: TokenStream) -> TokenStream {
     let attr = parse_macro_input!(attr as StructOptions);
     let cfg_attr = attr.options.convert_to_cfg_attr();
+    let (warnings, doc) = attr.options.explain_item();
 
-    let item_struct = parse_macro_input!(item as ItemStruct);
-    let &ItemStruct {
-        attrs: _,
-        vis: _,
-        struct_token: _,
-        ref ident,
-        ref generics,
-        fields: _,
-        semi_token: _,
-    } = &item_struct;
+    let mut item = parse_macro_input!(item as Item);
+    match item {
+        Item::Struct(ref mut item_struct) => item_struct.attrs.extend(doc),
+        Item::Enum(ref mut item_enum) => item_enum.attrs.extend(doc),
+        Item::Union(ref mut item_union) => item_union.attrs.extend(doc),
+        _ => {}
+    }
+    let (vis, ident, generics) = match item {
+        Item::Struct(ref item_struct) => {
+            (&item_struct.vis, &item_struct.ident, &item_struct.generics)
+        }
+        Item::Enum(ref item_enum) => (&item_enum.vis, &item_enum.ident, &item_enum.generics),
+        Item::Union(ref item_union) => (&item_union.vis, &item_union.ident, &item_union.generics),
+        ref other => {
+            return Error::new(
+                other.span(),
+                "#[sys_struct] expected a `struct`, `enum` or `union` definition",
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+
+    let alias_params = if generics.params.is_empty() {
+        TokenStream2::new()
+    } else {
+        let alias_params = generics
+            .params
+            .iter()
+            .map(|generic_param| match *generic_param {
+                GenericParam::Lifetime(ref lifetime_param) => {
+                    lifetime_param.lifetime.to_token_stream()
+                }
+                GenericParam::Type(ref type_param) => type_param.ident.to_token_stream(),
+                GenericParam::Const(ref const_param) => {
+                    let &ConstParam {
+                        attrs: _,
+                        ref const_token,
+                        ref ident,
+                        ref colon_token,
+                        ref ty,
+                        eq_token: _,
+                        default: _,
+                    } = const_param;
+                    quote!(#const_token #ident #colon_token #ty)
+                }
+            });
+        quote!(<#(#alias_params),*>)
+    };
+    let alias_usages = if generics.params.is_empty() {
+        TokenStream2::new()
+    } else {
+        let alias_usages = generics
+            .params
+            .iter()
+            .map(|generic_param| match *generic_param {
+                GenericParam::Lifetime(ref lifetime_param) => {
+                    lifetime_param.lifetime.to_token_stream()
+                }
+                GenericParam::Type(ref type_param) => type_param.ident.to_token_stream(),
+                GenericParam::Const(ref const_param) => const_param.ident.to_token_stream(),
+            });
+        quote!(<#(#alias_usages),*>)
+    };
+
+    let allowed_set: BTreeSet<_> = attr.options.allowed_set(|platform| match platform {
+        Platform::All | Platform::Posix => unreachable!("Should have been expanded"),
+        Platform::Linux => (platform, "Linux"),
+        Platform::Macos => (platform, "Macos"),
+        Platform::Windows => (platform, "Windows"),
+    });
+    let aliases = allowed_set.into_iter().map(|(platform, suffix)| {
+        let alias_ident = format_ident!("{ident}{suffix}");
+        let cfg_attrs = attr.options.cfg_attrs(&Cfg::target_os(platform));
+
+        quote! {
+            #cfg_attrs
+            #[allow(dead_code)]
+            #vis type #alias_ident #alias_params = #ident #alias_usages;
+        }
+    });
+
+    let (generic_traits, concrete_traits): (Vec<_>, Vec<_>) = attr
+        .traits
+        .into_iter()
+        .partition(|assertion| assertion.target.is_none());
+
+    let concrete_asserts = concrete_traits.into_iter().map(|assertion| {
+        let TraitAssertion { bound, target } = assertion;
+
+        quote! {
+            #cfg_attr
+            const _: () = {
+                fn _assert_traits<T: #bound + ?Sized>() {}
+                fn _check() { _assert_traits::<#target>(); }
+            };
+        }
+    });
 
-    let trait_asserts = if attr.traits.is_empty() {
+    let trait_asserts = if generic_traits.is_empty() {
         TokenStream2::new()
     } else {
-        let traits = attr.traits;
+        let traits = generic_traits.into_iter().map(|assertion| assertion.bound);
         let generics_where_clause = generics.where_clause.as_ref();
 
         let generics_without_lifetime = generics
@@ -407,8 +727,11 @@ pub fn sys_struct(attr: TokenStream, item: TokenStream) -> TokenStream {
 
     quote! {
         #cfg_attr
-        #item_struct
+        #item
+        #(#aliases)*
         #trait_asserts
+        #(#concrete_asserts)*
+        #warnings
     }
     .into()
 }
@@ -421,7 +744,24 @@ pub fn sys_struct(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///
 /// # Options
 ///
-/// Same as [`sys_function`]: `include(...)` and `exclude(...)` determine which platform modules are generated.
+/// - `include(...)` / `exclude(...)`: Same as [`sys_function`], determine which platform modules are generated.
+/// - `naming = "..."`: Template for the platform module names. `{name}` is replaced by the logical module
+///   name and `{platform}` by the platform keyword. Defaults to `"{platform}"` (e.g., `linux.rs`).
+///   - `naming = "{name}_{platform}"` generates `mod driver_linux;` backed by `driver_linux.rs`.
+///   - `naming = "{name}/{platform}"` generates `#[path = "driver/linux.rs"] mod driver_linux;`. Like any
+///     `#[path]` attribute, the path is relative to the directory of the current source file.
+/// - `layout = dir`: Generates a real module with the logical name (and the declared visibility) instead
+///   of an alias. Its platform files live in a directory named after it (e.g., `driver/linux.rs`); the
+///   matching one is included as a private `mod imp;` and glob re-exported (`pub use self::imp::*;`).
+///   With `naming`, the template names the files inside that directory.
+/// - `common`: Only with `layout = dir`. Additionally includes and re-exports shared code from `driver/common.rs`.
+/// - `linux | macos => "unix.rs"`: Backs a group of platforms with an explicit file, emitted as a `#[path]`
+///   attribute under a combined `#[cfg(...)]`. The module is named after the file stem (e.g., `mod unix;`).
+///   With `layout = dir`, the path is relative to the module directory. Platforms without a mapping keep
+///   using the naming template.
+///
+/// Custom templates allow several `#[platform_mod]` declarations to coexist in the same parent module
+/// without clashing `mod linux;` definitions.
 ///
 /// # Visibility Behavior
 ///
@@ -434,21 +774,135 @@ pub fn sys_struct(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// **Why?** This ensures that external consumers of your crate must be explicit about the platform they are accessing
 /// (e.g., `my_crate::linux::MyStruct`), while allowing you to use the generic name (e.g., `driver::MyStruct`)
 /// conveniently within your own code.
+///
+/// Both halves can be configured:
+///
+/// - `platform_vis = inherit | private | pub(...)`: Visibility of the platform modules. Defaults to `inherit`.
+/// - `alias_vis = private | inherit | pub(...)`: Visibility of the logical alias. Defaults to `private`.
+///   Use e.g. `alias_vis = pub` to re-export the logical name as the stable API of a library
+///   (this requires the platform modules to be visible enough to be re-exported).
+///
+/// Neither option applies to `layout = dir`, where the logical module itself carries the declared visibility.
+///
+/// # Unsupported Platforms
+///
+/// On platforms outside the generated set, the logical name does not exist, which leads to confusing
+/// "unresolved module" errors. `fallback` handles these platforms explicitly:
+///
+/// - `fallback = "unsupported.rs"`: Maps the logical name to a stub module backed by the given file on every
+///   other platform. The module is named after the logical module (e.g., `mod driver_fallback;`).
+/// - `fallback = error`: Emits a single `compile_error!` on every other platform.
+///
+/// # API Parity
+///
+/// With `check_parity`, the macro reads the files of *all* generated platform modules from disk, parses them
+/// and compares their public items (functions, types, constants, re-exports and inherent methods) by name and
+/// signature. Items missing in or differing between the platform files become compile errors on every host,
+/// not just on the platform whose file is incomplete.
+///
+/// `dir = "..."` sets the directory `mod linux;` resolves to, relative to the crate's `Cargo.toml`. Without it,
+/// the files are located relative to the source file containing the attribute, which requires the `local-file`
+/// feature (and Rust 1.88). Since macros cannot see enclosing inline modules (`mod foo { ... }`), declarations
+/// inside them always need `dir = "..."`.
+///
+/// # File Verification
+///
+/// With `verify_files`, every file the module expects (platform files, `common.rs` and the fallback file) must
+/// exist, otherwise a compile error is reported on any host. Platform files of platforms outside the platform
+/// set (e.g., a leftover `macos.rs` with `exclude(macos)`) are reported as warnings. The files are located the
+/// same way as for `check_parity`.
+///
+/// # Contracts
+///
+/// `contract = DriverApi` turns the platform layer into a compiler-checked interface: every platform module
+/// (and the fallback module, if any) must provide a type `Imp` implementing the trait `DriverApi`. The macro
+/// asserts this at compile time and adds a type alias named after the logical module in `UpperCamelCase`
+/// (e.g., `pub type Driver = driver::Imp;`) with the declared visibility.
+///
+/// # Use Paths
+///
+/// On `use` statements with a path, the macro does not declare any modules. Instead, it aliases items
+/// of existing platform modules: the platform module is inserted after the first segment (leading
+/// `self`, `super` and `crate` are skipped), and one `#[cfg(...)]`-guarded `use` is generated per
+/// platform.
+///
+/// ```rust,ignore
+/// // use sys::linux::platform as driver; (on Linux), use sys::windows::platform as driver; ...
+/// #[platform_mod]
+/// use sys::platform as driver;
+///
+/// // use imp::linux::{self as driver, Error}; (on Linux), ...
+/// #[platform_mod]
+/// use imp::{self as driver, Error};
+/// ```
+///
+/// Only `include(...)` and `exclude(...)` apply to this form.
+///
+/// # Documentation for All Platforms
+///
+/// With `docs_all_platforms`, documentation builds (`cfg(doc)`) include the modules of *every* platform, not just
+/// the host's. The platform modules are compiled on `cfg(any(doc, ...))`, while the logical alias keeps pointing
+/// to the host's module. With `layout = dir` and inline modules, the other platforms' code is added as
+/// documentation-only submodules named after the platform (e.g., `driver::windows`). Combine it with `doc_cfg`
+/// for per-platform badges. The other platforms' files must still compile on the documentation host.
+///
+/// # Inline Modules
+///
+/// Small crates can keep all platform code in one file by giving the module a body. Nested modules
+/// marked with platform attributes become the platform blocks: each one is guarded by the matching
+/// `#[cfg(...)]` and glob re-exported into the logical module. All other items are shared.
+///
+/// ```rust
+/// # use platify::platform_mod;
+/// #[platform_mod]
+/// pub mod driver {
+///     pub fn name() -> &'static str {
+///         imp::NAME
+///     }
+///
+///     #[posix]
+///     mod imp {
+///         pub const NAME: &str = "posix";
+///     }
+///
+///     #[windows]
+///     mod imp {
+///         pub const NAME: &str = "windows";
+///     }
+/// }
+/// ```
+///
+/// A block may carry its own `#[cfg(...)]`, which is merged with the platform predicate into a single
+/// attribute (e.g., `#[cfg(feature = "x")] #[linux] mod imp { ... }` becomes
+/// `#[cfg(all(feature = "x", target_os = "linux"))]`). Two such blocks may cover the same platform, as
+/// they can be alternatives of each other (`feature = "x"` and `not(feature = "x")`), but not a block
+/// with and one without a `#[cfg]`. A platform only covered by blocks with `#[cfg]`s gets a warning
+/// unless their predicates together always hold, as the module is empty where none of them does.
+///
+/// Every platform of the set must be covered by exactly one block. Options that refer to platform
+/// files (`naming`, `layout`, `common`, `platform_vis`, `alias_vis`, `check_parity`, `verify_files`, `dir`,
+/// `fallback = "..."` and file mappings) cannot be used with inline modules.
 #[proc_macro_attribute]
 pub fn platform_mod(attr: TokenStream, item: TokenStream) -> TokenStream {
     struct DModInfo {
         attrs: Vec<Attribute>,
         vis: Visibility,
         ident: proc_macro2::Ident,
+        content: Option<Vec<Item>>,
     }
 
-    let attr = parse_macro_input!(attr as AttrOptions);
-    let allowed_set: BTreeSet<_> = attr.allowed_set(|platform| match platform {
-        Platform::All | Platform::Posix => unreachable!("Should have been expanded"),
-        Platform::Linux => "linux",
-        Platform::Macos => "macos",
-        Platform::Windows => "windows",
-    });
+    let attr = parse_macro_input!(attr as ModOptions);
+    if let Some((span, _)) = attr.options.world {
+        return Error::new(
+            span,
+            "`world` cannot be used with #[platform_mod]: platform modules only exist for the listed \
+            platforms, use `fallback` for the others",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let allowed_set: BTreeSet<_> = attr.options.allowed_set(|platform| platform);
+    let (warnings, doc) = attr.options.explain_item();
 
     let mod_info = match parse::<ItemUse>(item.clone()) {
         Ok(item_use) => {
@@ -470,14 +924,19 @@ pub fn platform_mod(attr: TokenStream, item: TokenStream) -> TokenStream {
 
             let use_ident = match tree {
                 UseTree::Name(use_name) => use_name.ident,
-                other @ (UseTree::Path(_)
-                | UseTree::Rename(_)
-                | UseTree::Glob(_)
-                | UseTree::Group(_)) => {
+                tree @ UseTree::Path(_) => {
+                    return platform_use_paths(&attr, &allowed_set, &attrs, &vis, &tree)
+                        .unwrap_or_else(Error::into_compile_error)
+                        .into();
+                }
+                other @ (UseTree::Rename(_) | UseTree::Glob(_) | UseTree::Group(_)) => {
                     return Error::new(
-					    other.span(),
-					    "#[platform_mod] on `use` statements only supports simple direct aliases (e.g., `use name;`)"
-				    ).to_compile_error().into();
+                        other.span(),
+                        "#[platform_mod] on `use` statements expects a module name (e.g., `use name;`) \
+                        or a path into the platform modules (e.g., `use sys::platform as driver;`)",
+                    )
+                    .to_compile_error()
+                    .into();
                 }
             };
 
@@ -485,12 +944,11 @@ pub fn platform_mod(attr: TokenStream, item: TokenStream) -> TokenStream {
                 attrs,
                 vis,
                 ident: use_ident,
+                content: None,
             }
         }
         Err(_) => match parse::<ItemMod>(item) {
             Ok(item_mod) => {
-                let item_mod_span = item_mod.span();
-
                 let ItemMod {
                     attrs,
                     vis,
@@ -510,15 +968,12 @@ pub fn platform_mod(attr: TokenStream, item: TokenStream) -> TokenStream {
                     .into();
                 }
 
-                if content.is_some() {
-                    return Error::new(
-					    item_mod_span,
-					    "#[platform_mod] does not support inline modules with a body `{ ... }`.\n\
-					    Please use a declaration like `mod name;` to allow swapping the file based on the platform."
-				    ).to_compile_error().into();
+                DModInfo {
+                    attrs,
+                    vis,
+                    ident,
+                    content: content.map(|(_, items)| items),
                 }
-
-                DModInfo { attrs, vis, ident }
             }
             Err(_) => {
                 return Error::new(
@@ -529,202 +984,2709 @@ pub fn platform_mod(attr: TokenStream, item: TokenStream) -> TokenStream {
         },
     };
 
-    let DModInfo { attrs, vis, ident } = mod_info;
+    let DModInfo {
+        attrs,
+        vis,
+        ident,
+        content,
+    } = mod_info;
 
-    let mods = allowed_set.into_iter().map(|platform| {
-        let platform_ident = format_ident!("{platform}");
+    if content.is_some() {
+        if let Some(option) = attr.file_only_option() {
+            return Error::new(
+                attr.options.span,
+                format!(
+                    "`{option}` cannot be used with inline modules: there are no platform files"
+                ),
+            )
+            .to_compile_error()
+            .into();
+        }
+    }
 
-        quote! {
-            #[cfg(target_os = #platform)]
-            #(#attrs)*
-            #vis mod #platform_ident;
-            #[cfg(target_os = #platform)]
-            #(#attrs)*
-            use #platform_ident as #ident;
+    let parity_check = attr.check_parity.map(|span| {
+        match SourceDirs::locate(attr.dir.as_ref(), span)
+            .and_then(|dirs| attr.platform_files(&ident, &allowed_set, &dirs))
+        {
+            Ok(files) => check_parity(&files, span),
+            Err(err) => err.to_compile_error(),
         }
     });
 
-    quote!(#(#mods)*).into()
-}
-
-// ##################################### IMPLEMENTATION #####################################
-
-mod keywords {
-    use syn::custom_keyword;
-
-    custom_keyword!(traits);
-
-    custom_keyword!(exclude);
-    custom_keyword!(include);
+    let file_check = attr.verify_files.map(|span| {
+        SourceDirs::locate(attr.dir.as_ref(), span)
+            .and_then(|dirs| attr.verify_files(&ident, &allowed_set, &dirs, span))
+            .unwrap_or_else(Error::into_compile_error)
+    });
 
-    custom_keyword!(all);
-    custom_keyword!(posix);
-    custom_keyword!(linux);
-    custom_keyword!(macos);
-    custom_keyword!(windows);
-}
+    let supported = Cfg::platforms(allowed_set.iter().copied());
+    let unsupported = supported.clone().not();
+    // The logical module itself always exists, only its content depends on the platform.
+    let doc_cfg_attr = attr.options.doc_cfg_attr(&supported);
+    let fallback_error = matches!(attr.fallback, Some(ModFallback::Error)).then(|| {
+        let platforms = allowed_set
+            .iter()
+            .map(|platform| platform.keyword())
+            .collect::<Vec<_>>()
+            .join(", ");
+        unsupported_target_error(
+            &supported,
+            &format!("`{ident}` is not available for this target: #[platform_mod] only provides {platforms}"),
+        )
+    });
+    let fallback_file = match attr.fallback {
+        Some(ModFallback::File(ref path)) => Some(path),
+        Some(ModFallback::Error) | None => None,
+    };
 
-#[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
-enum Platform {
-    All,
-    Posix,
-    Linux,
-    Macos,
-    Windows,
-}
+    let contract = attr.contract.as_ref().map(|contract| {
+        // With a fallback module, the logical name exists on every platform.
+        let cfg_attr = fallback_file.is_none().then(|| quote!(#[cfg(#supported)]));
+        let alias_ident = format_ident!("{}", upper_camel_case(&ident.unraw().to_string()));
 
-impl Platform {
-    #[must_use]
-    fn expand(self) -> Vec<Self> {
-        match self {
-            Self::All => vec![Self::Linux, Self::Macos, Self::Windows],
-            Self::Posix => vec![Self::Linux, Self::Macos],
-            Self::Linux | Self::Macos | Self::Windows => vec![self],
+        quote! {
+            #cfg_attr
+            const _: () = {
+                fn _assert_contract<T: #contract + ?Sized>() {}
+                fn _check() { _assert_contract::<#ident::Imp>(); }
+            };
+            #cfg_attr
+            #vis type #alias_ident = #ident::Imp;
         }
-    }
-}
+    });
 
-impl Parse for Platform {
-    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
-        let lookahead = input.lookahead1();
-        if lookahead.peek(keywords::all) {
-            input.parse::<keywords::all>()?;
-            Ok(Self::All)
-        } else if lookahead.peek(keywords::posix) {
-            input.parse::<keywords::posix>()?;
-            Ok(Self::Posix)
-        } else if lookahead.peek(keywords::linux) {
-            input.parse::<keywords::linux>()?;
-            Ok(Self::Linux)
-        } else if lookahead.peek(keywords::macos) {
-            input.parse::<keywords::macos>()?;
-            Ok(Self::Macos)
-        } else if lookahead.peek(keywords::windows) {
-            input.parse::<keywords::windows>()?;
-            Ok(Self::Windows)
-        } else {
-            Err(lookahead.error())
+    if let Some(items) = content {
+        let blocks = match inline_platform_blocks(items, &allowed_set, &attr.options) {
+            Ok(blocks) => blocks,
+            Err(err) => return err.to_compile_error().into(),
+        };
+
+        return quote! {
+            #(#attrs)*
+            #(#doc)*
+            #doc_cfg_attr
+            #vis mod #ident {
+                #blocks
+            }
+            #fallback_error
+            #contract
+            #warnings
         }
+        .into();
     }
-}
 
-struct AttrOptions {
-    span: Span2,
-    exclude: HashSet<Platform>,
-    include: HashSet<Platform>,
-}
+    let (mapped, remaining) = match attr.mapped_files(&allowed_set) {
+        Ok(mapped_files) => mapped_files,
+        Err(err) => return err.to_compile_error().into(),
+    };
 
-impl AttrOptions {
-    #[must_use]
-    fn allowed_set<B: FromIterator<O>, M: Fn(Platform) -> O, O>(&self, mapping: M) -> B {
-        let all_includes = self
-            .include
-            .iter()
-            .copied()
-            .flat_map(Platform::expand)
-            .collect::<HashSet<_>>();
-        let all_excludes = self
-            .exclude
-            .iter()
-            .copied()
-            .flat_map(Platform::expand)
-            .collect::<HashSet<_>>();
-        all_includes
-            .difference(&all_excludes)
-            .map(|platform| mapping(*platform))
-            .collect()
-    }
+    if attr.layout == ModLayout::Dir {
+        let fallback = fallback_file.map(|path| {
+            quote! {
+                #[cfg(#unsupported)]
+                #[path = #path]
+                mod imp;
+                #[cfg(#unsupported)]
+                pub use self::imp::*;
+            }
+        });
+        let common = attr.common.then(|| {
+            quote! {
+                mod common;
+                pub use self::common::*;
+            }
+        });
+        let imps = remaining.into_iter().map(|platform| {
+            let target_os = platform.target_os();
+            let path = format!("{}.rs", attr.render_name(&ident, platform));
+            let doc_ident = format_ident!("{}", platform.keyword());
+            // For documentation, other platforms' files are included as modules named after the platform.
+            let doc_module = attr
+                .options
+                .doc_stub_cfg_attrs(&Cfg::target_os(platform))
+                .map(|stub_cfg_attrs| {
+                    quote! {
+                        #stub_cfg_attrs
+                        #[path = #path]
+                        pub mod #doc_ident;
+                    }
+                });
 
-    #[must_use]
-    fn convert_to_cfg_attr(&self) -> TokenStream2 {
-        let allowed_set: BTreeSet<_> = self.allowed_set(|platform| match platform {
-            Platform::All | Platform::Posix => unreachable!("Should have been expanded"),
-            Platform::Linux => "linux",
-            Platform::Macos => "macos",
-            Platform::Windows => "windows",
+            quote! {
+                #[cfg(target_os = #target_os)]
+                #[path = #path]
+                mod imp;
+                #[cfg(target_os = #target_os)]
+                pub use self::imp::*;
+                #doc_module
+            }
         });
+        let mapped_imps = mapped.into_iter().map(|(platforms, path)| {
+            let cfg_attrs = Cfg::platforms(platforms);
+            let doc_module = attr
+                .options
+                .doc_stub_cfg_attrs(&cfg_attrs)
+                .map(|stub_cfg_attrs| match ModOptions::file_module_name(path) {
+                    Ok(doc_ident) => quote! {
+                        #stub_cfg_attrs
+                        #[path = #path]
+                        pub mod #doc_ident;
+                    },
+                    Err(err) => err.to_compile_error(),
+                });
 
-        let error = if allowed_set.is_empty() {
-            Error::new(
-				self.span,
-				"Configuration excludes all platforms: 'include' and 'exclude' cancel each other out",
-			)
-				.to_compile_error()
-        } else {
-            TokenStream2::new()
-        };
+            quote! {
+                #[cfg(#cfg_attrs)]
+                #[path = #path]
+                mod imp;
+                #[cfg(#cfg_attrs)]
+                pub use self::imp::*;
+                #doc_module
+            }
+        });
 
-        let mut cfg_attrs = quote!(#(target_os = #allowed_set),*);
-        if allowed_set.len() != 1 {
-            cfg_attrs = quote!(any(#cfg_attrs));
+        return quote! {
+            #(#attrs)*
+            #(#doc)*
+            #doc_cfg_attr
+            #vis mod #ident {
+                #common
+                #(#imps)*
+                #(#mapped_imps)*
+                #fallback
+            }
+            #fallback_error
+            #parity_check
+            #file_check
+            #contract
+            #warnings
         }
+        .into();
+    }
+
+    let platform_vis = attr.platform_vis.as_ref().map_or_else(
+        || vis.to_token_stream(),
+        |platform_vis| platform_vis.resolve(&vis),
+    );
+    let alias_vis = attr
+        .alias_vis
+        .as_ref()
+        .map_or_else(TokenStream2::new, |alias_vis| alias_vis.resolve(&vis));
+    let fallback = fallback_file.map(|path| {
+        // Named after the logical module, so several modules may share a fallback file.
+        let fallback_ident = format_ident!("{ident}_fallback");
 
         quote! {
-            #error
-            #[cfg(#cfg_attrs)]
+            #[cfg(#unsupported)]
+            #[path = #path]
+            #(#attrs)*
+            #platform_vis mod #fallback_ident;
+            #[cfg(#unsupported)]
+            #(#attrs)*
+            #alias_vis use #fallback_ident as #ident;
         }
-    }
-}
-
+    });
+    let mods = remaining.into_iter().map(|platform| {
+        let cfg_attrs = attr.options.cfg_attrs(&Cfg::target_os(platform));
+        let alias_cfg_attrs = attr.options.platform_cfg_attrs(&Cfg::target_os(platform));
+        let (platform_ident, path) = match attr.module_name(&ident, platform) {
+            Ok(module_name) => module_name,
+            Err(err) => return err.to_compile_error(),
+        };
+        let path_attr = path.map(|path| quote!(#[path = #path]));
+
+        quote! {
+            #cfg_attrs
+            #path_attr
+            #(#attrs)*
+            #platform_vis mod #platform_ident;
+            #alias_cfg_attrs
+            #(#attrs)*
+            #(#doc)*
+            #alias_vis use #platform_ident as #ident;
+        }
+    });
+
+    let mapped_mods = mapped.into_iter().map(|(platforms, path)| {
+        let predicate = Cfg::platforms(platforms);
+        let cfg_attrs = attr.options.cfg_attrs(&predicate);
+        let alias_cfg_attrs = attr.options.platform_cfg_attrs(&predicate);
+        let module_ident = match ModOptions::file_module_name(path) {
+            Ok(module_ident) => module_ident,
+            Err(err) => return err.to_compile_error(),
+        };
+
+        quote! {
+            #cfg_attrs
+            #[path = #path]
+            #(#attrs)*
+            #platform_vis mod #module_ident;
+            #alias_cfg_attrs
+            #(#attrs)*
+            #(#doc)*
+            #alias_vis use #module_ident as #ident;
+        }
+    });
+
+    quote! {
+        #(#mods)*
+        #(#mapped_mods)*
+        #fallback
+        #fallback_error
+        #parity_check
+        #file_check
+        #contract
+        #warnings
+    }
+    .into()
+}
+
+/// Declares type aliases whose definition depends on the platform.
+///
+/// Each alias takes a table of arms in braces instead of a single type. Every arm maps one or more
+/// platforms (separated by `|`) to the type used on them, and a trailing `_ => ...` arm covers all
+/// remaining platforms. One `#[cfg(...)]`-guarded alias is generated per arm.
+///
+/// ```rust
+/// # use std::ffi::c_void;
+/// platify::sys_type! {
+///     /// The native handle type of the operating system.
+///     pub type RawHandle = {
+///         linux | macos => i32,
+///         windows => *mut c_void,
+///     };
+/// }
+/// ```
+///
+/// # Options
+///
+/// The table must cover every platform of its platform set exactly once, otherwise a compile error
+/// is emitted. The set defaults to `all` and can be narrowed with `include(...)` and `exclude(...)`
+/// inside the braces (e.g., `{ exclude(windows), linux => u32, macos => u64 }`). `doc_cfg` annotates
+/// each alias with its platforms, `docs_all_platforms` is not supported.
+#[proc_macro]
+pub fn sys_type(item: TokenStream) -> TokenStream {
+    let type_tables = parse_macro_input!(item with parse_all::<TypeTable>);
+
+    type_tables
+        .into_iter()
+        .map(|type_table| {
+            let TypeTable {
+                attrs,
+                vis,
+                type_token,
+                ident,
+                generics,
+                table,
+            } = type_table;
+
+            match table.resolve() {
+                Ok(arms) => {
+                    let warnings = table.warnings(&arms);
+                    let aliases = arms.iter().map(|arm| {
+                        let &ResolvedArm {
+                            ref platforms,
+                            others,
+                            value: ty,
+                        } = arm;
+                        let doc = table.options.availability_doc(platforms, others);
+                        let cfg_attrs = table
+                            .options
+                            .platform_cfg_attrs(&table.options.predicate(platforms, others));
+
+                        quote! {
+                            #cfg_attrs
+                            #(#attrs)*
+                            #(#doc)*
+                            #vis #type_token #ident #generics = #ty;
+                        }
+                    });
+
+                    quote! {
+                        #(#aliases)*
+                        #warnings
+                    }
+                }
+                Err(err) => err.to_compile_error(),
+            }
+        })
+        .collect::<TokenStream2>()
+        .into()
+}
+
+/// Declares a constant whose value depends on the platform.
+///
+/// The declaration is written without a value. The attribute maps one or more platforms
+/// (separated by `|`) to the value used on them, and `_ = ...` covers all remaining platforms.
+/// One `#[cfg(...)]`-guarded constant with the declared type is generated per entry.
+///
+/// ```rust
+/// # use platify::sys_const;
+/// #[sys_const(linux = 0x8912, macos = 0xc020_6924, windows = 0)]
+/// pub const SIOCGIFCONF: u32;
+///
+/// #[sys_const(windows = '\\', _ = '/')]
+/// pub const SEPARATOR: char;
+/// ```
+///
+/// # Options
+///
+/// Every platform of the platform set must be covered exactly once, otherwise a compile error is
+/// emitted. The set defaults to `all` and can be narrowed with `include(...)` and `exclude(...)`.
+/// `doc_cfg` annotates each constant with its platforms, `docs_all_platforms` is not supported.
+#[proc_macro_attribute]
+pub fn sys_const(attr: TokenStream, item: TokenStream) -> TokenStream {
+    expand_value_decl(attr, item, ValueDecl::parse_const)
+}
+
+/// Declares a static whose value depends on the platform.
+///
+/// Works exactly like [`sys_const`], but for `static` (and `static mut`) declarations.
+///
+/// ```rust
+/// # use platify::sys_static;
+/// #[sys_static(posix = "/var/run/app.sock", windows = r"\\.\pipe\app")]
+/// pub static SOCKET_PATH: &str;
+/// ```
+#[proc_macro_attribute]
+pub fn sys_static(attr: TokenStream, item: TokenStream) -> TokenStream {
+    expand_value_decl(attr, item, ValueDecl::parse_static)
+}
+
+/// Selects an expression based on the platform.
+///
+/// Works like a `match` over the target platform: every arm maps one or more platforms (separated
+/// by `|`) to an expression, and `_ => ...` covers all remaining platforms. Only the arm matching
+/// the target is compiled, so the other arms may refer to platform-specific items.
+///
+/// ```rust
+/// let shell = platify::select! {
+///     linux | macos => "/bin/sh",
+///     windows => "cmd.exe",
+/// };
+///
+/// let line_ending = platify::select! { windows => "\r\n", _ => "\n" };
+/// ```
+///
+/// # Options
+///
+/// Overlapping arms and platforms without an arm are compile errors. The platform set that has to
+/// be covered defaults to `all` and can be narrowed with `include(...)` and `exclude(...)`.
+/// Building for a target no arm covers (e.g., FreeBSD, or Linux with `include(windows)`) fails with
+/// a single error naming the covered platforms.
+/// The documentation options `doc_cfg` and `docs_all_platforms` are not supported.
+#[proc_macro]
+pub fn select(item: TokenStream) -> TokenStream {
+    let table = parse_macro_input!(item as PlatformTable<Expr>);
+    // Expressions have no documentation.
+    if table.options.doc_cfg {
+        return Error::new(
+            table.options.span,
+            "`doc_cfg` cannot be used with select!: only items are documented",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    match table.resolve() {
+        Ok(arms) => {
+            let warnings = table.warnings(&arms);
+            let predicates = arms
+                .iter()
+                .map(|arm| table.options.predicate(&arm.platforms, arm.others))
+                .collect::<Vec<_>>();
+            let supported = Cfg::any(predicates.iter().cloned());
+            let covered = arms
+                .iter()
+                .flat_map(|arm| arm.platforms.iter().map(|platform| platform.keyword()))
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect::<Vec<_>>()
+                .join(", ");
+            let unsupported_error = unsupported_target_error(
+                &supported,
+                &format!("select! has no arm for this target (covered: {covered})"),
+            );
+            // Keeps the `match` exhaustive next to the error, so it is the only one reported.
+            let unsupported_arm = (supported != Cfg::TRUE).then(|| {
+                let unsupported = supported.clone().not();
+                quote! {
+                    #[cfg(#unsupported)]
+                    () => ::core::unreachable!(),
+                }
+            });
+            let arms = arms.iter().zip(&predicates).map(|(arm, predicate)| {
+                let expr = arm.value;
+
+                quote! {
+                    #[cfg(#predicate)]
+                    () => #expr,
+                }
+            });
+
+            quote! {
+                {
+                    #warnings
+                    #unsupported_error
+                    match () {
+                        #(#arms)*
+                        #unsupported_arm
+                    }
+                }
+            }
+        }
+        Err(err) => err.to_compile_error(),
+    }
+    .into()
+}
+
+/// Declares the platforms the crate supports.
+///
+/// Takes a comma-separated list of the keywords accepted by `include(...)` and emits a single
+/// `compile_error!` on every other target, naming the crate and the supported platforms. Without it,
+/// building for an unsupported target fails with one error per item that does not exist there.
+///
+/// ```rust
+/// platify::supported_platforms!(posix, windows);
+/// ```
+///
+/// On FreeBSD, this fails with ``crate `my_crate` does not support this target (supported: linux,
+/// macos, windows)``.
+#[proc_macro]
+pub fn supported_platforms(item: TokenStream) -> TokenStream {
+    let platforms = match Punctuated::<Platform, Token![,]>::parse_terminated.parse(item) {
+        Ok(platforms) if platforms.is_empty() => {
+            return Error::new(Span2::call_site(), "expected at least one platform")
+                .to_compile_error()
+                .into();
+        }
+        Ok(platforms) => platforms
+            .into_iter()
+            .flat_map(Platform::expand)
+            .collect::<BTreeSet<_>>(),
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    // Proc macros run inside rustc, which Cargo invokes with the package of the calling crate.
+    let crate_name = env::var("CARGO_PKG_NAME")
+        .or_else(|_| env::var("CARGO_CRATE_NAME"))
+        .map_or_else(
+            |_| "this crate".to_owned(),
+            |name| format!("crate `{name}`"),
+        );
+
+    expand_supported_platforms(&platforms, &crate_name).into()
+}
+
+// ##################################### IMPLEMENTATION #####################################
+
+/// Emits the `compile_error!` of [`supported_platforms`] for every target outside `platforms`.
+#[must_use]
+fn expand_supported_platforms(platforms: &BTreeSet<Platform>, crate_name: &str) -> TokenStream2 {
+    let supported_list = platforms
+        .iter()
+        .map(|platform| platform.keyword())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    unsupported_target_error(
+        &Cfg::platforms(platforms.iter().copied()),
+        &format!("{crate_name} does not support this target (supported: {supported_list})"),
+    )
+}
+
+fn expand_value_decl(
+    attr: TokenStream,
+    item: TokenStream,
+    parse_decl: fn(ParseStream<'_>) -> syn::Result<ValueDecl>,
+) -> TokenStream {
+    let table = match PlatformTable::<Expr>::parse_with_separator::<Token![=]>.parse(attr) {
+        Ok(table) => table,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let ValueDecl {
+        attrs,
+        vis,
+        kind,
+        ident,
+        ty,
+    } = match parse_decl.parse(item) {
+        Ok(value_decl) => value_decl,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    match table.resolve() {
+        Ok(arms) => {
+            let warnings = table.warnings(&arms);
+            arms.iter()
+                .map(|arm| {
+                    let &ResolvedArm {
+                        ref platforms,
+                        others,
+                        value,
+                    } = arm;
+                    let doc = table.options.availability_doc(platforms, others);
+                    let cfg_attrs = table
+                        .options
+                        .platform_cfg_attrs(&table.options.predicate(platforms, others));
+                    // Warnings go into the value, which also works for associated constants.
+                    let value = if warnings.is_empty() {
+                        value.to_token_stream()
+                    } else {
+                        quote!({ #warnings #value })
+                    };
+
+                    quote! {
+                        #cfg_attrs
+                        #(#attrs)*
+                        #(#doc)*
+                        #vis #kind #ident: #ty = #value;
+                    }
+                })
+                .collect::<TokenStream2>()
+        }
+        Err(err) => err.to_compile_error(),
+    }
+    .into()
+}
+
+mod keywords {
+    use syn::custom_keyword;
+
+    custom_keyword!(traits);
+    custom_keyword!(naming);
+    custom_keyword!(layout);
+    custom_keyword!(flat);
+    custom_keyword!(dir);
+    custom_keyword!(common);
+    custom_keyword!(platform_vis);
+    custom_keyword!(alias_vis);
+    custom_keyword!(inherit);
+    custom_keyword!(private);
+    custom_keyword!(fallback);
+    custom_keyword!(check_parity);
+    custom_keyword!(verify_files);
+    custom_keyword!(strict);
+    custom_keyword!(explain);
+    custom_keyword!(doc_cfg);
+    custom_keyword!(docs_all_platforms);
+    custom_keyword!(world);
+    custom_keyword!(open);
+    custom_keyword!(closed);
+    custom_keyword!(contract);
+    custom_keyword!(error);
+
+    custom_keyword!(exclude);
+    custom_keyword!(include);
+
+    custom_keyword!(all);
+    custom_keyword!(posix);
+    custom_keyword!(linux);
+    custom_keyword!(macos);
+    custom_keyword!(windows);
+}
+
+#[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum Platform {
+    All,
+    Posix,
+    Linux,
+    Macos,
+    Windows,
+}
+
+impl Platform {
+    /// All platforms and groups, in the order they are listed in diagnostics.
+    const KEYWORDS: [Self; 5] = [
+        Self::All,
+        Self::Posix,
+        Self::Linux,
+        Self::Macos,
+        Self::Windows,
+    ];
+
+    #[must_use]
+    fn keyword(self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Posix => "posix",
+            Self::Linux => "linux",
+            Self::Macos => "macos",
+            Self::Windows => "windows",
+        }
+    }
+
+    #[must_use]
+    fn target_os(self) -> &'static str {
+        match self {
+            Self::All | Self::Posix => unreachable!("Should have been expanded"),
+            Self::Linux => "linux",
+            Self::Macos => "macos",
+            Self::Windows => "windows",
+        }
+    }
+
+    /// Returns the human-readable name of the platform (e.g., `macOS`).
+    #[must_use]
+    fn display_name(self) -> &'static str {
+        match self {
+            Self::All | Self::Posix => unreachable!("Should have been expanded"),
+            Self::Linux => "Linux",
+            Self::Macos => "macOS",
+            Self::Windows => "Windows",
+        }
+    }
+
+    #[must_use]
+    fn expand(self) -> Vec<Self> {
+        match self {
+            Self::All => vec![Self::Linux, Self::Macos, Self::Windows],
+            Self::Posix => vec![Self::Linux, Self::Macos],
+            Self::Linux | Self::Macos | Self::Windows => vec![self],
+        }
+    }
+}
+
+impl Parse for Platform {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let keywords = Self::KEYWORDS
+            .iter()
+            .map(|platform| format!("`{}`", platform.keyword()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if !input.peek(syn::Ident) {
+            return Err(input.error(format!("expected a platform: one of {keywords}")));
+        }
+
+        let ident = input.parse::<proc_macro2::Ident>()?;
+        let name = ident.to_string();
+        if let Some(&platform) = Self::KEYWORDS
+            .iter()
+            .find(|platform| platform.keyword() == name)
+        {
+            return Ok(platform);
+        }
+
+        let suggestion = Self::KEYWORDS
+            .iter()
+            .map(|platform| (edit_distance(&name, platform.keyword()), platform.keyword()))
+            .filter(|&(distance, keyword)| distance <= 2 || keyword.starts_with(&name))
+            .min()
+            .map(|(_, keyword)| format!("; did you mean `{keyword}`?"))
+            .unwrap_or_default();
+
+        Err(Error::new(
+            ident.span(),
+            format!("unknown platform `{name}`{suggestion}\nvalid platforms are {keywords}"),
+        ))
+    }
+}
+
+/// Returns the Levenshtein distance between `a` and `b`.
+#[must_use]
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, &b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+struct AttrOptions {
+    span: Span2,
+    exclude: HashSet<Platform>,
+    include: HashSet<Platform>,
+    /// Redundant or contradictory entries of `include(...)`/`exclude(...)`, reported as warnings.
+    lints: Vec<(Span2, String)>,
+    /// Set by the `explain` option.
+    explain: Option<Span2>,
+    /// Set by the `doc_cfg` option.
+    doc_cfg: bool,
+    /// Set by the `docs_all_platforms` option.
+    docs_all_platforms: bool,
+    /// Set by the `world = ...` option.
+    world: Option<(Span2, World)>,
+}
+
+/// How `include(...)` and `exclude(...)` treat platforms other than Linux, macOS and Windows.
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum World {
+    /// Only the listed platforms exist: `exclude(windows)` means Linux and macOS.
+    Closed,
+    /// Other platforms exist as well: `exclude(windows)` means every platform except Windows.
+    Open,
+}
+
+impl AttrOptions {
+    #[must_use]
+    fn allowed_set<B: FromIterator<O>, M: Fn(Platform) -> O, O>(&self, mapping: M) -> B {
+        let all_includes = self
+            .include
+            .iter()
+            .copied()
+            .flat_map(Platform::expand)
+            .collect::<HashSet<_>>();
+        let all_excludes = self
+            .exclude
+            .iter()
+            .copied()
+            .flat_map(Platform::expand)
+            .collect::<HashSet<_>>();
+        all_includes
+            .difference(&all_excludes)
+            .map(|platform| mapping(*platform))
+            .collect()
+    }
+
+    #[must_use]
+    fn convert_to_cfg_attr(&self) -> TokenStream2 {
+        self.convert_with(Self::cfg_attrs)
+    }
+
+    /// Like [`Self::convert_to_cfg_attr`], but ignores `docs_all_platforms`.
+    #[must_use]
+    fn convert_to_platform_cfg_attr(&self) -> TokenStream2 {
+        self.convert_with(Self::platform_cfg_attrs)
+    }
+
+    #[must_use]
+    fn convert_with(&self, cfg_attrs: fn(&Self, &Cfg) -> TokenStream2) -> TokenStream2 {
+        let allowed_set: BTreeSet<_> = self.allowed_set(|platform| platform);
+
+        let error = if allowed_set.is_empty() {
+            self.empty_set_error().to_compile_error()
+        } else {
+            TokenStream2::new()
+        };
+
+        let cfg_attrs = cfg_attrs(
+            self,
+            &self.predicate(&allowed_set, self.includes_other_platforms()),
+        );
+
+        quote! {
+            #error
+            #cfg_attrs
+        }
+    }
+
+    /// Whether the platform set also contains platforms other than Linux, macOS and Windows. This is
+    /// the case in the open world unless `include(...)` lists specific platforms.
+    #[must_use]
+    fn includes_other_platforms(&self) -> bool {
+        matches!(self.world, Some((_, World::Open))) && self.include.contains(&Platform::All)
+    }
+
+    /// Returns the predicate matching `platforms` and, with `others`, every platform other than
+    /// Linux, macOS and Windows. The latter is the complement of the remaining known platforms.
+    #[must_use]
+    fn predicate(&self, platforms: &BTreeSet<Platform>, others: bool) -> Cfg {
+        if others {
+            Cfg::platforms(
+                Platform::All
+                    .expand()
+                    .into_iter()
+                    .filter(|platform| !platforms.contains(platform)),
+            )
+            .not()
+        } else {
+            Cfg::platforms(platforms.iter().copied())
+        }
+    }
+
+    /// Returns the `#[cfg]` attribute of an item available where `predicate` holds, followed by its
+    /// `doc(cfg)` annotation if `doc_cfg` is set.
+    #[must_use]
+    fn cfg_attrs(&self, predicate: &Cfg) -> TokenStream2 {
+        let doc_cfg_attr = self.doc_cfg_attr(predicate);
+        let predicate = if self.docs_all_platforms {
+            Cfg::any([Cfg::name("doc"), predicate.clone()])
+        } else {
+            predicate.clone()
+        };
+        // Items available everywhere need no attribute at all.
+        let cfg_attr = (predicate != Cfg::TRUE).then(|| quote!(#[cfg(#predicate)]));
+        quote! {
+            #cfg_attr
+            #doc_cfg_attr
+        }
+    }
+
+    /// Like [`Self::cfg_attrs`], but ignores `docs_all_platforms`. Used for items that exist once per
+    /// platform under the same name and therefore cannot all be compiled for documentation.
+    #[must_use]
+    fn platform_cfg_attrs(&self, predicate: &Cfg) -> TokenStream2 {
+        let doc_cfg_attr = self.doc_cfg_attr(predicate);
+        let cfg_attr = (*predicate != Cfg::TRUE).then(|| quote!(#[cfg(#predicate)]));
+        quote! {
+            #cfg_attr
+            #doc_cfg_attr
+        }
+    }
+
+    /// Returns the attributes of a documentation-only stand-in for an item available where
+    /// `predicate` holds, or `None` unless `docs_all_platforms` is set.
+    #[must_use]
+    fn doc_stub_cfg_attrs(&self, predicate: &Cfg) -> Option<TokenStream2> {
+        let stub_predicate = Cfg::all([Cfg::name("doc"), predicate.clone().not()]);
+        // An item available everywhere never needs a stand-in.
+        (self.docs_all_platforms && stub_predicate != Cfg::FALSE).then(|| {
+            let doc_cfg_attr = self.doc_cfg_attr(predicate);
+            quote! {
+                #[cfg(#stub_predicate)]
+                #doc_cfg_attr
+            }
+        })
+    }
+
+    /// Returns the `#[cfg_attr(docsrs, doc(cfg(...)))]` annotation for `predicate` if `doc_cfg` is set.
+    #[must_use]
+    fn doc_cfg_attr(&self, predicate: &Cfg) -> Option<TokenStream2> {
+        (self.doc_cfg && *predicate != Cfg::TRUE)
+            .then(|| quote!(#[cfg_attr(docsrs, doc(cfg(#predicate)))]))
+    }
+
+    /// Returns the lints of the configuration and, with `explain`, a note describing how the
+    /// platform set was computed and which `cfgs` were generated from it.
+    #[must_use]
+    fn warnings(&self, cfgs: &str) -> TokenStream2 {
+        let mut warnings = self
+            .lints
+            .iter()
+            .map(|&(span, ref message)| compile_warning(span, message))
+            .collect::<TokenStream2>();
+
+        if let Some(span) = self.explain {
+            let describe = |platforms: &HashSet<Platform>| {
+                let platforms = platforms.iter().copied().collect::<BTreeSet<_>>();
+                let listed = platforms
+                    .iter()
+                    .map(|platform| platform.keyword())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let expanded = platforms
+                    .iter()
+                    .copied()
+                    .flat_map(Platform::expand)
+                    .map(Platform::keyword)
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("({listed}) -> {{{expanded}}}")
+            };
+            let platforms = self
+                .allowed_set::<BTreeSet<_>, _, _>(|platform| platform)
+                .into_iter()
+                .map(Platform::keyword)
+                .chain(self.includes_other_platforms().then_some("..."))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            warnings.extend(compile_warning(
+                span,
+                &format!(
+                    "explain: include{}, exclude{}, platforms {{{platforms}}}, generated {cfgs}",
+                    describe(&self.include),
+                    describe(&self.exclude),
+                ),
+            ));
+        }
+
+        warnings
+    }
+
+    /// Returns `#[doc]` attributes listing `platforms` (and, with `others`, other platforms) if
+    /// `explain` is set.
+    #[must_use]
+    fn availability_doc(&self, platforms: &BTreeSet<Platform>, others: bool) -> Vec<Attribute> {
+        if self.explain.is_none() {
+            return Vec::new();
+        }
+
+        let names = platforms
+            .iter()
+            .map(|platform| platform.display_name())
+            .chain(others.then_some("other platforms"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let line = format!("Available on: {names}");
+        vec![parse_quote!(#[doc = ""]), parse_quote!(#[doc = #line])]
+    }
+
+    /// Returns the warnings and `#[doc]` attributes of an item generated for the whole platform set.
+    #[must_use]
+    fn explain_item(&self) -> (TokenStream2, Vec<Attribute>) {
+        let platforms: BTreeSet<_> = self.allowed_set(|platform| platform);
+        let others = self.includes_other_platforms();
+        let cfg = self.predicate(&platforms, others);
+        let warnings = self.warnings(&format!("#[cfg({cfg})]"));
+        (warnings, self.availability_doc(&platforms, others))
+    }
+
+    #[must_use]
+    fn empty_set_error(&self) -> Error {
+        Error::new(
+            self.span,
+            "Configuration excludes all platforms: 'include' and 'exclude' cancel each other out",
+        )
+    }
+}
+
+/// Emits a `compile_error!` with `message` on every target not matching the `supported` predicate.
+#[must_use]
+fn unsupported_target_error(supported: &Cfg, message: &str) -> TokenStream2 {
+    // Supported everywhere: there is no target to reject.
+    if *supported == Cfg::TRUE {
+        return TokenStream2::new();
+    }
+
+    let unsupported = supported.clone().not();
+    quote! {
+        #[cfg(#unsupported)]
+        ::core::compile_error!(#message);
+    }
+}
+
+/// Emits `message` as a warning at `span`.
+///
+/// Proc macros cannot emit warnings on stable, so this references a `#[deprecated]` item whose note
+/// carries the message.
+#[must_use]
+fn compile_warning(span: Span2, message: &str) -> TokenStream2 {
+    quote_spanned! {span=>
+        const _: () = {
+            #[deprecated(note = #message)]
+            #[allow(non_camel_case_types)]
+            struct platify;
+            let _ = platify;
+        };
+    }
+}
+
+/// A `cfg` predicate such as `any(target_os = "linux", target_os = "macos")`.
+///
+/// Predicates are built through the constructors, which keep them normalised (see
+/// [`Cfg::simplify`]), so equal predicates always render identically.
+#[derive(Clone, Eq, Ord, PartialEq, PartialOrd)]
+enum Cfg {
+    /// A plain option, e.g. `doc` or `unix`.
+    Name(String),
+    /// An option with a value, e.g. `target_os = "linux"`.
+    KeyValue(String, String),
+    Not(Box<Cfg>),
+    All(Vec<Cfg>),
+    Any(Vec<Cfg>),
+    /// A predicate the simplifier does not understand (e.g., `version("1.80")`), kept as written.
+    Verbatim(String),
+}
+
+impl Cfg {
+    /// The predicate that always holds, `all()`.
+    const TRUE: Self = Self::All(Vec::new());
+    /// The predicate that never holds, `any()`.
+    const FALSE: Self = Self::Any(Vec::new());
+
+    #[must_use]
+    fn name(name: &str) -> Self {
+        Self::Name(name.to_owned())
+    }
+
+    #[must_use]
+    fn target_os(platform: Platform) -> Self {
+        Self::KeyValue("target_os".to_owned(), platform.target_os().to_owned())
+    }
+
+    /// Builds the predicate matching any of the given (already expanded) platforms.
+    #[must_use]
+    fn platforms<I: IntoIterator<Item = Platform>>(platforms: I) -> Self {
+        Self::any(platforms.into_iter().map(Self::target_os))
+    }
+
+    #[must_use]
+    fn not(self) -> Self {
+        Self::Not(Box::new(self)).simplify()
+    }
+
+    #[must_use]
+    fn all<I: IntoIterator<Item = Self>>(terms: I) -> Self {
+        Self::All(terms.into_iter().collect()).simplify()
+    }
+
+    #[must_use]
+    fn any<I: IntoIterator<Item = Self>>(terms: I) -> Self {
+        Self::Any(terms.into_iter().collect()).simplify()
+    }
+
+    /// Normalises the predicate: nested `all`/`any` are flattened, duplicates, neutral and absorbed
+    /// terms are removed, double negations are resolved, single terms are unwrapped and the terms
+    /// are sorted. As `target_os` has exactly one value, a `target_os = "..."` term of an `all` also
+    /// decides every other `target_os` term in it.
+    #[must_use]
+    fn simplify(self) -> Self {
+        match self {
+            Self::Name(_) | Self::KeyValue(..) | Self::Verbatim(_) => self,
+            Self::Not(inner) => match inner.simplify() {
+                Self::Not(inner) => *inner,
+                other if other == Self::TRUE => Self::FALSE,
+                other if other == Self::FALSE => Self::TRUE,
+                other => Self::Not(Box::new(other)),
+            },
+            Self::All(terms) => Self::simplify_terms(terms, true),
+            Self::Any(terms) => Self::simplify_terms(terms, false),
+        }
+    }
+
+    /// Simplifies the terms of an `all` (`conjunction`) or `any`.
+    #[must_use]
+    fn simplify_terms(terms: Vec<Self>, conjunction: bool) -> Self {
+        let flatten = |terms: Vec<Self>| {
+            let mut flat = Vec::new();
+            for term in terms {
+                match term.simplify() {
+                    Self::All(inner) if conjunction => flat.extend(inner),
+                    Self::Any(inner) if !conjunction => flat.extend(inner),
+                    other => flat.push(other),
+                }
+            }
+            flat
+        };
+        let mut terms = flatten(terms);
+
+        if conjunction {
+            let target_os = terms.iter().find_map(|term| match *term {
+                Self::KeyValue(ref key, ref value) if key == "target_os" => Some(value.clone()),
+                Self::Name(_)
+                | Self::KeyValue(..)
+                | Self::Not(_)
+                | Self::All(_)
+                | Self::Any(_)
+                | Self::Verbatim(_) => None,
+            });
+            if let Some(target_os) = target_os {
+                let decided = Self::KeyValue("target_os".to_owned(), target_os);
+                terms = flatten(
+                    terms
+                        .into_iter()
+                        .map(|term| {
+                            if term == decided {
+                                term
+                            } else {
+                                term.assume_target_os(&decided)
+                            }
+                        })
+                        .collect(),
+                );
+            }
+        }
+
+        let (absorbing, junction): (_, fn(Vec<Self>) -> Self) = if conjunction {
+            (Self::FALSE, Self::All)
+        } else {
+            (Self::TRUE, Self::Any)
+        };
+        let contradiction = terms.iter().any(|term| match *term {
+            Self::Not(ref inner) => terms.contains(inner),
+            Self::Name(_)
+            | Self::KeyValue(..)
+            | Self::All(_)
+            | Self::Any(_)
+            | Self::Verbatim(_) => false,
+        });
+        if contradiction || terms.contains(&absorbing) {
+            return absorbing;
+        }
+
+        terms.sort();
+        terms.dedup();
+        // `all(a, any(a, b))` is `a`, and so is `any(a, all(a, b))`.
+        let snapshot = terms.clone();
+        terms.retain(|term| match *term {
+            Self::Any(ref inner) if conjunction => !inner.iter().any(|t| snapshot.contains(t)),
+            Self::All(ref inner) if !conjunction => !inner.iter().any(|t| snapshot.contains(t)),
+            Self::Name(_)
+            | Self::KeyValue(..)
+            | Self::Not(_)
+            | Self::All(_)
+            | Self::Any(_)
+            | Self::Verbatim(_) => true,
+        });
+
+        if terms.len() == 1 {
+            terms.remove(0)
+        } else {
+            junction(terms)
+        }
+    }
+
+    /// Replaces every `target_os` term by whether it matches `decided`.
+    #[must_use]
+    fn assume_target_os(self, decided: &Self) -> Self {
+        match self {
+            Self::KeyValue(ref key, _) if key == "target_os" => {
+                if self == *decided {
+                    Self::TRUE
+                } else {
+                    Self::FALSE
+                }
+            }
+            Self::Name(_) | Self::KeyValue(..) | Self::Verbatim(_) => self,
+            Self::Not(inner) => Self::Not(Box::new(inner.assume_target_os(decided))),
+            Self::All(terms) => Self::All(
+                terms
+                    .into_iter()
+                    .map(|term| term.assume_target_os(decided))
+                    .collect(),
+            ),
+            Self::Any(terms) => Self::Any(
+                terms
+                    .into_iter()
+                    .map(|term| term.assume_target_os(decided))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Parses a predicate the simplifier understands: `true`, `false`, options and `all`/`any`/`not`.
+    fn parse_known(input: ParseStream<'_>) -> syn::Result<Self> {
+        if input.peek(LitBool) {
+            return Ok(if input.parse::<LitBool>()?.value {
+                Self::TRUE
+            } else {
+                Self::FALSE
+            });
+        }
+
+        let name = input.parse::<syn::Ident>()?;
+        if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            return Ok(Self::KeyValue(
+                name.to_string(),
+                input.parse::<LitStr>()?.value(),
+            ));
+        }
+        if !input.peek(token::Paren) {
+            return Ok(Self::Name(name.to_string()));
+        }
+
+        let content;
+        parenthesized!(content in input);
+        let mut terms = Punctuated::<Self, Token![,]>::parse_terminated(&content)?
+            .into_iter()
+            .collect::<Vec<_>>();
+        if name == "all" {
+            Ok(Self::all(terms))
+        } else if name == "any" {
+            Ok(Self::any(terms))
+        } else if name == "not" && terms.len() == 1 {
+            Ok(terms.remove(0).not())
+        } else {
+            Err(Error::new(
+                name.span(),
+                "expected `all(...)`, `any(...)` or `not(...)` with a single predicate",
+            ))
+        }
+    }
+}
+
+impl Parse for Cfg {
+    /// Parses a single predicate, which extends to the next top-level comma.
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let mut raw = TokenStream2::new();
+        while !input.is_empty() && !input.peek(Token![,]) {
+            raw.extend([input.parse::<proc_macro2::TokenTree>()?]);
+        }
+        if raw.is_empty() {
+            return Err(input.error("expected a cfg predicate"));
+        }
+
+        // Whatever the simplifier does not understand is left for the compiler to evaluate.
+        Ok(Self::parse_known
+            .parse2(raw.clone())
+            .unwrap_or_else(|_| Self::Verbatim(raw.to_string())))
+    }
+}
+
+impl quote::ToTokens for Cfg {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let cfg = match *self {
+            Self::Name(ref name) => {
+                let name = format_ident!("{name}");
+                quote!(#name)
+            }
+            Self::KeyValue(ref key, ref value) => {
+                let key = format_ident!("{key}");
+                quote!(#key = #value)
+            }
+            Self::Not(ref inner) => quote!(not(#inner)),
+            Self::All(ref terms) => quote!(all(#(#terms),*)),
+            Self::Any(ref terms) => quote!(any(#(#terms),*)),
+            // Rendered from valid tokens, so it always parses again.
+            Self::Verbatim(ref raw) => raw.parse().unwrap_or_default(),
+        };
+        tokens.extend(cfg);
+    }
+}
+
+impl fmt::Display for Cfg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |terms: &[Self]| {
+            terms
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        match *self {
+            Self::Name(ref name) => f.write_str(name),
+            Self::KeyValue(ref key, ref value) => write!(f, "{key} = {value:?}"),
+            Self::Not(ref inner) => write!(f, "not({inner})"),
+            Self::All(ref terms) => write!(f, "all({})", join(terms)),
+            Self::Any(ref terms) => write!(f, "any({})", join(terms)),
+            Self::Verbatim(ref raw) => f.write_str(raw),
+        }
+    }
+}
+
 impl Parse for AttrOptions {
     fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
-        parse_attributes(input, false).map(|options| {
-            let StructOptions { options, traits } = options;
-            assert_eq!(traits.len(), 0, "Implementation error");
-            options
+        parse_attributes(input, |_, _| Ok(false))
+    }
+}
+
+struct StructOptions {
+    options: AttrOptions,
+    traits: Vec<TraitAssertion>,
+}
+
+/// A single entry of `traits(...)`: either `Send` (checked for all valid generics)
+/// or `Send for Handle<u8>` (checked for one concrete instantiation).
+struct TraitAssertion {
+    bound: syn::Path,
+    target: Option<Type>,
+}
+
+impl Parse for TraitAssertion {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let bound = input.parse()?;
+        let target = if input.peek(Token![for]) {
+            input.parse::<Token![for]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        Ok(Self { bound, target })
+    }
+}
+
+impl Parse for StructOptions {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let mut traits = Vec::new();
+        let options = parse_attributes(input, |input, lookahead| {
+            if !lookahead.peek(keywords::traits) {
+                return Ok(false);
+            }
+            input.parse::<keywords::traits>()?;
+
+            let content;
+            parenthesized!(content in input);
+
+            traits.extend(content.parse_terminated(TraitAssertion::parse, token::Comma)?);
+            Ok(true)
+        })?;
+
+        Ok(Self { options, traits })
+    }
+}
+
+/// Visibility of an item generated by `#[platform_mod]`, relative to the declared one.
+enum VisOption {
+    Inherit,
+    Private,
+    Explicit(Visibility),
+}
+
+impl VisOption {
+    #[must_use]
+    fn resolve(&self, declared: &Visibility) -> TokenStream2 {
+        match *self {
+            Self::Inherit => declared.to_token_stream(),
+            Self::Private => TokenStream2::new(),
+            Self::Explicit(ref vis) => vis.to_token_stream(),
+        }
+    }
+}
+
+impl Parse for VisOption {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        if input.peek(keywords::inherit) {
+            input.parse::<keywords::inherit>()?;
+            return Ok(Self::Inherit);
+        }
+        if input.peek(keywords::private) {
+            input.parse::<keywords::private>()?;
+            return Ok(Self::Private);
+        }
+
+        match input.parse()? {
+            Visibility::Inherited => {
+                Err(input.error("expected `inherit`, `private`, `pub` or `pub(...)`"))
+            }
+            vis @ (Visibility::Public(_) | Visibility::Restricted(_)) => Ok(Self::Explicit(vis)),
+        }
+    }
+}
+
+/// Where the files of a `#[platform_mod]` are looked up on disk.
+struct SourceDirs {
+    /// The directory `#[path]` attributes on file-level modules are relative to.
+    file_dir: PathBuf,
+    /// The directory `mod name;` declarations resolve to.
+    module_dir: PathBuf,
+}
+
+impl SourceDirs {
+    /// Locates the directories from `dir = "..."` (relative to `CARGO_MANIFEST_DIR`) or, if absent,
+    /// from the source file containing the macro invocation.
+    fn locate(dir: Option<&LitStr>, span: Span2) -> syn::Result<Self> {
+        if let Some(dir) = dir {
+            let dir = env::var_os("CARGO_MANIFEST_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_default()
+                .join(dir.value());
+            return Ok(Self {
+                file_dir: dir.clone(),
+                module_dir: dir,
+            });
+        }
+
+        // `Span::local_file` is stable since Rust 1.88, above the crate's MSRV.
+        #[cfg(feature = "local-file")]
+        #[allow(clippy::incompatible_msrv)]
+        let file = proc_macro::Span::call_site().local_file();
+        #[cfg(not(feature = "local-file"))]
+        let file = None::<PathBuf>;
+
+        let Some(file) = file else {
+            let message = if cfg!(feature = "local-file") {
+                "Could not determine the source file of this declaration: \
+                specify the directory of the platform files with `dir = \"...\"`"
+            } else {
+                "Specify the directory of the platform files with `dir = \"...\"`, \
+                or enable the `local-file` feature to locate them next to this source file"
+            };
+            return Err(Error::new(span, message));
+        };
+
+        // Cargo names the crate (and a binary) after the root file of `src/bin`, `tests` and
+        // `examples` targets, so their stems identify these roots.
+        let crate_names = ["CARGO_CRATE_NAME", "CARGO_BIN_NAME"]
+            .into_iter()
+            .filter_map(|name| env::var(name).ok())
+            .collect::<Vec<_>>();
+        Ok(Self::for_file(&file, &crate_names))
+    }
+
+    /// Derives the directories from the source file containing the declaration. Crate roots and
+    /// `mod.rs` files resolve `mod linux;` next to themselves, other files in a directory named
+    /// after them.
+    #[must_use]
+    fn for_file(file: &Path, crate_names: &[String]) -> Self {
+        let file_dir = file.parent().map(Path::to_path_buf).unwrap_or_default();
+        let stem = file.file_stem().and_then(OsStr::to_str).unwrap_or_default();
+        let is_root = matches!(stem, "lib" | "main" | "mod")
+            || crate_names.iter().any(|crate_name| crate_name == stem);
+        let module_dir = if is_root {
+            file_dir.clone()
+        } else {
+            file_dir.join(stem)
+        };
+
+        Self {
+            file_dir,
+            module_dir,
+        }
+    }
+}
+
+/// Expands a `use` path into one `#[cfg(...)]`-guarded `use` per platform, with the platform module
+/// inserted after the first segment (e.g., `use sys::platform;` -> `use sys::linux::platform;`).
+fn platform_use_paths(
+    attr: &ModOptions,
+    allowed_set: &BTreeSet<Platform>,
+    attrs: &[Attribute],
+    vis: &Visibility,
+    tree: &UseTree,
+) -> syn::Result<TokenStream2> {
+    let option = attr
+        .file_only_option()
+        .or_else(|| attr.fallback.is_some().then_some("fallback"))
+        .or_else(|| attr.contract.is_some().then_some("contract"))
+        .or_else(|| {
+            attr.options
+                .docs_all_platforms
+                .then_some("docs_all_platforms")
+        });
+    if let Some(option) = option {
+        return Err(Error::new(
+            attr.options.span,
+            format!(
+                "`{option}` cannot be used with `use` paths: only `include` and `exclude` apply"
+            ),
+        ));
+    }
+
+    let (warnings, doc) = attr.options.explain_item();
+    let uses = allowed_set
+        .iter()
+        .map(|&platform| {
+            let cfg_attrs = attr.options.platform_cfg_attrs(&Cfg::target_os(platform));
+            let platform_tree = insert_platform_segment(tree, platform)?;
+
+            Ok(quote! {
+                #cfg_attrs
+                #(#attrs)*
+                #(#doc)*
+                #vis use #platform_tree;
+            })
+        })
+        .collect::<syn::Result<TokenStream2>>()?;
+
+    Ok(quote! {
+        #uses
+        #warnings
+    })
+}
+
+/// Inserts the module of `platform` after the first segment of `tree` that is not `self`, `super`
+/// or `crate`.
+fn insert_platform_segment(tree: &UseTree, platform: Platform) -> syn::Result<TokenStream2> {
+    match *tree {
+        UseTree::Path(ref use_path) => {
+            let &UsePath {
+                ref ident,
+                colon2_token: _,
+                tree: ref subtree,
+            } = use_path;
+
+            if ident == "self" || ident == "super" || ident == "crate" {
+                let subtree = insert_platform_segment(subtree, platform)?;
+                Ok(quote!(#ident::#subtree))
+            } else {
+                let platform_ident = format_ident!("{}", platform.keyword());
+                Ok(quote!(#ident::#platform_ident::#subtree))
+            }
+        }
+        UseTree::Name(_) | UseTree::Rename(_) | UseTree::Glob(_) | UseTree::Group(_) => {
+            Err(Error::new(
+                tree.span(),
+                "Expected a path into the platform modules (e.g., `sys::platform`)",
+            ))
+        }
+    }
+}
+
+/// Expands the body of an inline `#[platform_mod]` module.
+///
+/// Nested modules marked with platform attributes (e.g., `#[linux] mod imp { ... }`) are guarded by
+/// the matching `#[cfg(...)]` and glob re-exported. All other items are shared by every platform.
+fn inline_platform_blocks(
+    items: Vec<Item>,
+    allowed_set: &BTreeSet<Platform>,
+    options: &AttrOptions,
+) -> syn::Result<TokenStream2> {
+    let mut errors = Vec::new();
+    let mut output = TokenStream2::new();
+    // The combined `#[cfg]`s of the blocks covering each platform, `all()` for blocks without any.
+    let mut gates = BTreeMap::<Platform, Vec<Cfg>>::new();
+
+    for item in items {
+        let Item::Mod(mut item_mod) = item else {
+            item.to_tokens(&mut output);
+            continue;
+        };
+
+        let mut markers = Vec::new();
+        item_mod.attrs.retain(|attr| {
+            let marker = match attr.meta {
+                Meta::Path(ref path) => syn::parse2::<Platform>(path.to_token_stream()).ok(),
+                Meta::List(_) | Meta::NameValue(_) => None,
+            };
+            markers.extend(marker);
+            marker.is_none()
+        });
+        if markers.is_empty() {
+            item_mod.to_tokens(&mut output);
+            continue;
+        }
+
+        let mut block_cfgs = Vec::new();
+        for attr in mem::take(&mut item_mod.attrs) {
+            match attr.meta {
+                Meta::List(ref list) if list.path.is_ident("cfg") => {
+                    match list.parse_args::<Cfg>() {
+                        Ok(cfg) => block_cfgs.push(cfg),
+                        Err(err) => errors.push(err),
+                    }
+                }
+                Meta::Path(_) | Meta::List(_) | Meta::NameValue(_) => item_mod.attrs.push(attr),
+            }
+        }
+
+        let gate = Cfg::all(block_cfgs.iter().cloned());
+        let block_span = item_mod.ident.span();
+        let doc_ident = format_ident!(
+            "{}",
+            markers
+                .iter()
+                .map(|platform| platform.keyword())
+                .collect::<Vec<_>>()
+                .join("_")
+        );
+        let mut block_set = BTreeSet::new();
+        for platform in markers.into_iter().flat_map(Platform::expand) {
+            if !allowed_set.contains(&platform) {
+                errors.push(Error::new(
+                    block_span,
+                    format!(
+                        "`{}` is not part of the platform set of this module",
+                        platform.keyword()
+                    ),
+                ));
+            } else if gates.get(&platform).into_iter().flatten().any(|previous| {
+                // Blocks with their own `#[cfg]`s may be alternatives of each other (e.g.,
+                // `feature = "x"` and `not(feature = "x")`), which cannot always be decided here.
+                (*previous == Cfg::TRUE || gate == Cfg::TRUE)
+                    && Cfg::all([previous.clone(), gate.clone()]) != Cfg::FALSE
+            }) {
+                errors.push(Error::new(
+                    block_span,
+                    format!(
+                        "`{}` is already covered by a previous block",
+                        platform.keyword()
+                    ),
+                ));
+            } else {
+                block_set.insert(platform);
+            }
+        }
+        for &platform in &block_set {
+            gates.entry(platform).or_default().push(gate.clone());
+        }
+
+        let platform_predicate = Cfg::platforms(block_set);
+        // For documentation, other platforms' blocks are included as modules named after their markers.
+        if let (Some(stub_cfg_attrs), Some((_, ref items))) = (
+            options.doc_stub_cfg_attrs(&platform_predicate),
+            &item_mod.content,
+        ) {
+            let attrs = &item_mod.attrs;
+            output.extend(quote! {
+                #stub_cfg_attrs
+                #(#[cfg(#block_cfgs)])*
+                #(#attrs)*
+                pub mod #doc_ident {
+                    #(#items)*
+                }
+            });
+        }
+
+        // The block's own `#[cfg]`s are merged into a single attribute with the platform predicate.
+        let predicate = Cfg::all(block_cfgs.iter().cloned().chain([platform_predicate]));
+        let block_ident = &item_mod.ident;
+        output.extend(quote! {
+            #[cfg(#predicate)]
+            #item_mod
+            #[cfg(#predicate)]
+            pub use self::#block_ident::*;
+        });
+    }
+
+    // Platforms only covered by gated blocks, grouped by where they lack a block.
+    let mut partially_covered = BTreeMap::<Cfg, Vec<&str>>::new();
+    for &platform in allowed_set {
+        let keyword = platform.keyword();
+        let uncovered = Cfg::any(gates.remove(&platform).unwrap_or_default()).not();
+        if uncovered == Cfg::TRUE {
+            errors.push(Error::new(
+                options.span,
+                format!(
+                    "Missing platform block for `{keyword}` (e.g., `#[{keyword}] mod imp {{ ... }}`)"
+                ),
+            ));
+        } else if uncovered != Cfg::FALSE {
+            partially_covered
+                .entry(uncovered)
+                .or_default()
+                .push(keyword);
+        }
+    }
+    for (uncovered, keywords) in partially_covered {
+        output.extend(compile_warning(
+            options.span,
+            &format!(
+                "No platform block for {} where `{uncovered}` holds",
+                keywords.join(", ")
+            ),
+        ));
+    }
+
+    match errors.into_iter().reduce(|mut combined, error| {
+        combined.combine(error);
+        combined
+    }) {
+        Some(error) => Err(error),
+        None => Ok(output),
+    }
+}
+
+/// Converts a `snake_case` module name into `UpperCamelCase` (e.g., `net_io` -> `NetIo`).
+#[must_use]
+fn upper_camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars).collect()
+            })
+        })
+        .collect()
+}
+
+/// Returns the file `mod name;` resolves to inside `dir`: `name.rs`, or `name/mod.rs` if only that exists.
+#[must_use]
+fn module_file(dir: &Path, ident: &proc_macro2::Ident) -> PathBuf {
+    // `mod r#type;` resolves to `type.rs`.
+    let name = ident.unraw().to_string();
+    let file = dir.join(format!("{name}.rs"));
+    let mod_rs = dir.join(name).join("mod.rs");
+    if !file.exists() && mod_rs.exists() {
+        mod_rs
+    } else {
+        file
+    }
+}
+
+/// Compares the public API of the given platform files and reports items that are missing in
+/// some of them or whose signatures differ.
+///
+/// Every file is also registered via `include_bytes!`, so changes to it trigger a rebuild.
+#[must_use]
+fn check_parity(files: &[PathBuf], span: Span2) -> TokenStream2 {
+    let mut errors = TokenStream2::new();
+    let mut tracked = Vec::new();
+    let mut apis = Vec::new();
+
+    for path in files {
+        let display = display_path(path);
+        let parsed = fs::read_to_string(path)
+            .map_err(|err| format!("Could not read `{display}`: {err}"))
+            .and_then(|source| {
+                syn::parse_file(&source)
+                    .map_err(|err| format!("Could not parse `{display}`: {err}"))
+            });
+
+        match parsed {
+            Ok(file) => {
+                tracked.push(fs::canonicalize(path).unwrap_or_else(|_| path.clone()));
+                apis.push((display, public_api(&file.items)));
+            }
+            Err(message) => errors.extend(Error::new(span, message).to_compile_error()),
+        }
+    }
+
+    let names = apis
+        .iter()
+        .flat_map(|&(_, ref api)| api.keys())
+        .collect::<BTreeSet<_>>();
+    for name in names {
+        let Some(&(ref reference_file, ref reference_api)) =
+            apis.iter().find(|&&(_, ref api)| api.contains_key(name))
+        else {
+            continue;
+        };
+        let reference = &reference_api[name];
+
+        for &(ref file, ref api) in &apis {
+            let message = match (api.get(name), reference) {
+                (None, _) => {
+                    format!("`{file}` is missing `{name}`, which is defined in `{reference_file}`")
+                }
+                (Some(&Some(ref signature)), &Some(ref reference)) if signature != reference => {
+                    format!(
+                        "`{name}` differs between `{reference_file}` and `{file}`: \
+                        `{reference}` vs. `{signature}`"
+                    )
+                }
+                (Some(_), _) => continue,
+            };
+            errors.extend(Error::new(span, message).to_compile_error());
+        }
+    }
+
+    let tracked = tracked
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>();
+
+    quote! {
+        #errors
+        #(const _: &[u8] = ::core::include_bytes!(#tracked);)*
+    }
+}
+
+/// Formats `path` for diagnostics, relative to `CARGO_MANIFEST_DIR` where possible.
+#[must_use]
+fn display_path(path: &Path) -> String {
+    env::var_os("CARGO_MANIFEST_DIR")
+        .and_then(|manifest_dir| path.strip_prefix(manifest_dir).ok())
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+/// Collects the public items of a platform file, mapped to their signature.
+///
+/// Re-exports have no signature (`None`) and only take part in the name comparison.
+#[must_use]
+fn public_api(items: &[Item]) -> BTreeMap<String, Option<String>> {
+    fn is_public(vis: &Visibility) -> bool {
+        !matches!(*vis, Visibility::Inherited)
+    }
+
+    fn collect_use_names(tree: &UseTree, names: &mut Vec<String>) {
+        match *tree {
+            UseTree::Path(ref use_path) => collect_use_names(&use_path.tree, names),
+            UseTree::Name(ref use_name) => names.push(use_name.ident.to_string()),
+            UseTree::Rename(ref use_rename) => names.push(use_rename.rename.to_string()),
+            UseTree::Glob(_) => {}
+            UseTree::Group(ref use_group) => {
+                for tree in &use_group.items {
+                    collect_use_names(tree, names);
+                }
+            }
+        }
+    }
+
+    let mut api = BTreeMap::new();
+    for item in items {
+        match *item {
+            Item::Fn(ref item_fn) if is_public(&item_fn.vis) => {
+                let sig = &item_fn.sig;
+                api.insert(sig.ident.to_string(), Some(signature_api(sig)));
+            }
+            Item::Struct(ref item_struct) if is_public(&item_struct.vis) => {
+                let ident = &item_struct.ident;
+                let generics = &item_struct.generics;
+                api.insert(
+                    ident.to_string(),
+                    Some(quote!(struct #ident #generics).to_string()),
+                );
+            }
+            Item::Enum(ref item_enum) if is_public(&item_enum.vis) => {
+                let ident = &item_enum.ident;
+                let generics = &item_enum.generics;
+                api.insert(
+                    ident.to_string(),
+                    Some(quote!(enum #ident #generics).to_string()),
+                );
+            }
+            Item::Union(ref item_union) if is_public(&item_union.vis) => {
+                let ident = &item_union.ident;
+                let generics = &item_union.generics;
+                api.insert(
+                    ident.to_string(),
+                    Some(quote!(union #ident #generics).to_string()),
+                );
+            }
+            Item::Trait(ref item_trait) if is_public(&item_trait.vis) => {
+                let ident = &item_trait.ident;
+                let generics = &item_trait.generics;
+                api.insert(
+                    ident.to_string(),
+                    Some(quote!(trait #ident #generics).to_string()),
+                );
+            }
+            Item::Type(ref item_type) if is_public(&item_type.vis) => {
+                let ident = &item_type.ident;
+                let generics = &item_type.generics;
+                api.insert(
+                    ident.to_string(),
+                    Some(quote!(type #ident #generics).to_string()),
+                );
+            }
+            Item::Const(ref item_const) if is_public(&item_const.vis) => {
+                let ident = &item_const.ident;
+                let ty = &item_const.ty;
+                api.insert(
+                    ident.to_string(),
+                    Some(quote!(const #ident: #ty).to_string()),
+                );
+            }
+            Item::Static(ref item_static) if is_public(&item_static.vis) => {
+                let mutability = &item_static.mutability;
+                let ident = &item_static.ident;
+                let ty = &item_static.ty;
+                api.insert(
+                    ident.to_string(),
+                    Some(quote!(static #mutability #ident: #ty).to_string()),
+                );
+            }
+            Item::Mod(ref item_mod) if is_public(&item_mod.vis) => {
+                let ident = &item_mod.ident;
+                api.insert(ident.to_string(), Some(quote!(mod #ident).to_string()));
+            }
+            Item::Use(ref item_use) if is_public(&item_use.vis) => {
+                let mut names = Vec::new();
+                collect_use_names(&item_use.tree, &mut names);
+                for name in names {
+                    api.entry(name).or_insert(None);
+                }
+            }
+            Item::Impl(ref item_impl) if item_impl.trait_.is_none() => {
+                let self_ty = match *item_impl.self_ty {
+                    Type::Path(ref type_path) => type_path
+                        .path
+                        .segments
+                        .last()
+                        .map(|segment| segment.ident.to_string()),
+                    _ => None,
+                }
+                .unwrap_or_else(|| item_impl.self_ty.to_token_stream().to_string());
+
+                for impl_item in &item_impl.items {
+                    if let ImplItem::Fn(ref impl_fn) = *impl_item {
+                        if is_public(&impl_fn.vis) {
+                            let sig = &impl_fn.sig;
+                            api.insert(
+                                format!("{self_ty}::{}", sig.ident),
+                                Some(signature_api(sig)),
+                            );
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    api
+}
+
+/// Renders the parts of `sig` callers depend on. Parameter names and patterns are implementation
+/// details of each platform file and are left out (e.g., `fn read(& mut [u8]) -> usize`).
+#[must_use]
+fn signature_api(sig: &Signature) -> String {
+    let &Signature {
+        ref constness,
+        ref asyncness,
+        ref unsafety,
+        ref abi,
+        fn_token: _,
+        ref ident,
+        ref generics,
+        paren_token: _,
+        ref inputs,
+        ref variadic,
+        ref output,
+    } = sig;
+    let where_clause = &generics.where_clause;
+    let inputs = inputs.iter().map(|input| match *input {
+        FnArg::Receiver(ref receiver) => receiver.ty.to_token_stream(),
+        FnArg::Typed(ref pat_type) => pat_type.ty.to_token_stream(),
+    });
+    let variadic = variadic.as_ref().map(|_| quote!(...));
+
+    quote! {
+        #constness #asyncness #unsafety #abi fn #ident #generics (#(#inputs,)* #variadic) #output
+        #where_clause
+    }
+    .to_string()
+}
+
+/// What `#[platform_mod]` generates for platforms outside its set.
+enum ModFallback {
+    /// A stub module backed by the given file.
+    File(LitStr),
+    /// A `compile_error!` on every unsupported target.
+    Error,
+}
+
+/// Groups of platforms sharing an explicitly mapped file.
+type MappedFiles<'a> = Vec<(BTreeSet<Platform>, &'a LitStr)>;
+
+/// How the files of a `#[platform_mod]` are laid out.
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum ModLayout {
+    /// `mod linux;` next to the declaring file, aliased to the logical name.
+    Flat,
+    /// A real `mod driver { ... }` whose platform files live in the `driver/` directory.
+    Dir,
+}
+
+struct ModOptions {
+    options: AttrOptions,
+    naming: Option<LitStr>,
+    layout: ModLayout,
+    common: bool,
+    platform_vis: Option<VisOption>,
+    alias_vis: Option<VisOption>,
+    fallback: Option<ModFallback>,
+    check_parity: Option<Span2>,
+    verify_files: Option<Span2>,
+    dir: Option<LitStr>,
+    contract: Option<syn::Path>,
+    /// Explicit files for groups of platforms (e.g., `linux | macos => "unix.rs"`).
+    paths: Vec<TableArm<LitStr>>,
+}
+
+impl ModOptions {
+    /// Returns the first option that only applies to modules backed by files, if any is set.
+    #[must_use]
+    fn file_only_option(&self) -> Option<&'static str> {
+        let &Self {
+            options: _,
+            ref naming,
+            layout,
+            common,
+            ref platform_vis,
+            ref alias_vis,
+            ref fallback,
+            check_parity,
+            verify_files,
+            ref dir,
+            contract: _,
+            ref paths,
+        } = self;
+
+        [
+            (naming.is_some(), "naming"),
+            (layout == ModLayout::Dir, "layout"),
+            (common, "common"),
+            (platform_vis.is_some(), "platform_vis"),
+            (alias_vis.is_some(), "alias_vis"),
+            (
+                matches!(*fallback, Some(ModFallback::File(_))),
+                "fallback = \"...\"",
+            ),
+            (check_parity.is_some(), "check_parity"),
+            (verify_files.is_some(), "verify_files"),
+            (dir.is_some(), "dir"),
+            (!paths.is_empty(), "platform => \"...\""),
+        ]
+        .into_iter()
+        .find_map(|(is_set, option)| is_set.then_some(option))
+    }
+
+    /// Returns the file backing each generated platform module.
+    fn platform_files(
+        &self,
+        logical: &proc_macro2::Ident,
+        platforms: &BTreeSet<Platform>,
+        dirs: &SourceDirs,
+    ) -> syn::Result<Vec<PathBuf>> {
+        let (mapped, remaining) = self.mapped_files(platforms)?;
+        let mapped_files = mapped.into_iter().map(|(_, path)| match self.layout {
+            ModLayout::Dir => Ok(dirs
+                .module_dir
+                .join(logical.unraw().to_string())
+                .join(path.value())),
+            ModLayout::Flat => Ok(dirs.file_dir.join(path.value())),
+        });
+
+        remaining
+            .into_iter()
+            .map(|platform| self.platform_file(logical, platform, dirs))
+            .chain(mapped_files)
+            .collect()
+    }
+
+    /// Returns the file backing the module of `platform` according to the naming template.
+    fn platform_file(
+        &self,
+        logical: &proc_macro2::Ident,
+        platform: Platform,
+        dirs: &SourceDirs,
+    ) -> syn::Result<PathBuf> {
+        match self.layout {
+            ModLayout::Dir => Ok(dirs
+                .module_dir
+                .join(logical.unraw().to_string())
+                .join(format!("{}.rs", self.render_name(logical, platform)))),
+            ModLayout::Flat => match self.module_name(logical, platform)? {
+                (_, Some(path)) => Ok(dirs.file_dir.join(path)),
+                (platform_ident, None) => Ok(module_file(&dirs.module_dir, &platform_ident)),
+            },
+        }
+    }
+
+    /// Reports missing files of the module as errors and platform files of platforms outside
+    /// `platforms` as warnings.
+    fn verify_files(
+        &self,
+        logical: &proc_macro2::Ident,
+        platforms: &BTreeSet<Platform>,
+        dirs: &SourceDirs,
+        span: Span2,
+    ) -> syn::Result<TokenStream2> {
+        let logical_dir = dirs.module_dir.join(logical.unraw().to_string());
+        let mut files = self.platform_files(logical, platforms, dirs)?;
+        if self.common {
+            files.push(module_file(&logical_dir, &format_ident!("common")));
+        }
+        if let Some(ModFallback::File(ref path)) = self.fallback {
+            files.push(match self.layout {
+                ModLayout::Dir => logical_dir.join(path.value()),
+                ModLayout::Flat => dirs.file_dir.join(path.value()),
+            });
+        }
+
+        let missing = files
+            .iter()
+            .filter(|file| !file.exists())
+            .map(|file| {
+                Error::new(
+                    span,
+                    format!(
+                        "The file `{}` of `{logical}` does not exist",
+                        display_path(file)
+                    ),
+                )
+            })
+            .reduce(|mut combined, error| {
+                combined.combine(error);
+                combined
+            });
+        if let Some(error) = missing {
+            return Err(error);
+        }
+
+        let mapped = self
+            .paths
+            .iter()
+            .flat_map(|arm| arm.platforms.iter().flatten().copied())
+            .flat_map(Platform::expand)
+            .collect::<BTreeSet<_>>();
+        let mut warnings = TokenStream2::new();
+        for platform in Platform::All.expand() {
+            if platforms.contains(&platform) || mapped.contains(&platform) {
+                continue;
+            }
+
+            let file = self.platform_file(logical, platform, dirs)?;
+            if file.exists() {
+                warnings.extend(compile_warning(
+                    span,
+                    &format!(
+                        "`{}` is never used: `{}` is not part of the platform set of `{logical}`",
+                        display_path(&file),
+                        platform.keyword()
+                    ),
+                ));
+            }
+        }
+        Ok(warnings)
+    }
+
+    /// Splits `platforms` into the groups mapped to an explicit file (`linux | macos => "unix.rs"`)
+    /// and the remaining platforms, which use the naming template.
+    fn mapped_files(
+        &self,
+        platforms: &BTreeSet<Platform>,
+    ) -> syn::Result<(MappedFiles<'_>, BTreeSet<Platform>)> {
+        let mut errors = Vec::new();
+        let mut mapped = Vec::new();
+        let mut remaining = platforms.clone();
+
+        for arm in &self.paths {
+            let mut group = BTreeSet::new();
+            for platform in arm
+                .platforms
+                .iter()
+                .flatten()
+                .copied()
+                .flat_map(Platform::expand)
+            {
+                if !platforms.contains(&platform) {
+                    errors.push(Error::new(
+                        arm.span,
+                        format!(
+                            "`{}` is not part of the platform set of this module",
+                            platform.keyword()
+                        ),
+                    ));
+                } else if !remaining.remove(&platform) {
+                    errors.push(Error::new(
+                        arm.span,
+                        format!(
+                            "`{}` is already mapped to a file by a previous entry",
+                            platform.keyword()
+                        ),
+                    ));
+                } else {
+                    group.insert(platform);
+                }
+            }
+            mapped.push((group, &arm.value));
+        }
+
+        match errors.into_iter().reduce(|mut combined, error| {
+            combined.combine(error);
+            combined
+        }) {
+            Some(error) => Err(error),
+            None => Ok((mapped, remaining)),
+        }
+    }
+
+    /// Returns the identifier of a module backed by `path`, derived from the stem of the file.
+    fn file_module_name(path: &LitStr) -> syn::Result<proc_macro2::Ident> {
+        let value = path.value();
+        let stem = Path::new(&value)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+
+        syn::parse_str(stem).map_err(|_| {
+            Error::new(
+                path.span(),
+                format!("The file name `{stem}` is not a valid module name"),
+            )
+        })
+    }
+
+    /// Renders the naming template (or the default `{platform}`) for `platform`.
+    #[must_use]
+    fn render_name(&self, logical: &proc_macro2::Ident, platform: Platform) -> String {
+        self.naming
+            .as_ref()
+            .map_or_else(|| "{platform}".to_owned(), LitStr::value)
+            .replace("{name}", &logical.unraw().to_string())
+            .replace("{platform}", platform.keyword())
+    }
+
+    /// Returns the identifier of the module backing `platform` and, if the naming template points
+    /// into a subdirectory, the value of its `#[path]` attribute.
+    fn module_name(
+        &self,
+        logical: &proc_macro2::Ident,
+        platform: Platform,
+    ) -> syn::Result<(proc_macro2::Ident, Option<String>)> {
+        let Some(ref naming) = self.naming else {
+            return Ok((format_ident!("{}", platform.keyword()), None));
+        };
+
+        let name = self.render_name(logical, platform);
+        let (ident, path) = if name.contains('/') {
+            (name.replace('/', "_"), Some(format!("{name}.rs")))
+        } else {
+            (name, None)
+        };
+
+        match syn::parse_str(&ident) {
+            Ok(ident) => Ok((ident, path)),
+            Err(_) => Err(Error::new(
+                naming.span(),
+                format!("The naming template produces `{ident}`, which is not a valid module name"),
+            )),
+        }
+    }
+}
+
+impl Parse for ModOptions {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let mut naming = None;
+        let mut layout = ModLayout::Flat;
+        let mut common = false;
+        let mut platform_vis = None;
+        let mut alias_vis = None;
+        let mut fallback = None;
+        let mut check_parity = None;
+        let mut verify_files = None;
+        let mut dir = None;
+        let mut contract = None;
+        let mut paths = Vec::new();
+        let options = parse_attributes(input, |input, lookahead| {
+            if lookahead.peek(keywords::naming) {
+                input.parse::<keywords::naming>()?;
+                input.parse::<Token![=]>()?;
+
+                let template = input.parse::<LitStr>()?;
+                if !template.value().contains("{platform}") {
+                    return Err(Error::new(
+                        template.span(),
+                        "The naming template must contain `{platform}`",
+                    ));
+                }
+                naming = Some(template);
+            } else if lookahead.peek(keywords::layout) {
+                input.parse::<keywords::layout>()?;
+                input.parse::<Token![=]>()?;
+
+                let value_lookahead = input.lookahead1();
+                if value_lookahead.peek(keywords::flat) {
+                    input.parse::<keywords::flat>()?;
+                    layout = ModLayout::Flat;
+                } else if value_lookahead.peek(keywords::dir) {
+                    input.parse::<keywords::dir>()?;
+                    layout = ModLayout::Dir;
+                } else {
+                    return Err(value_lookahead.error());
+                }
+            } else if lookahead.peek(keywords::common) {
+                input.parse::<keywords::common>()?;
+                common = true;
+            } else if lookahead.peek(keywords::platform_vis) {
+                input.parse::<keywords::platform_vis>()?;
+                input.parse::<Token![=]>()?;
+                platform_vis = Some(input.parse()?);
+            } else if lookahead.peek(keywords::alias_vis) {
+                input.parse::<keywords::alias_vis>()?;
+                input.parse::<Token![=]>()?;
+                alias_vis = Some(input.parse()?);
+            } else if lookahead.peek(keywords::check_parity) {
+                check_parity = Some(input.parse::<keywords::check_parity>()?.span);
+            } else if lookahead.peek(keywords::verify_files) {
+                verify_files = Some(input.parse::<keywords::verify_files>()?.span);
+            } else if lookahead.peek(keywords::dir) && input.peek2(Token![=]) {
+                input.parse::<keywords::dir>()?;
+                input.parse::<Token![=]>()?;
+                dir = Some(input.parse()?);
+            } else if lookahead.peek(keywords::contract) {
+                input.parse::<keywords::contract>()?;
+                input.parse::<Token![=]>()?;
+                contract = Some(input.parse()?);
+            } else if lookahead.peek(keywords::all)
+                || lookahead.peek(keywords::posix)
+                || lookahead.peek(keywords::linux)
+                || lookahead.peek(keywords::macos)
+                || lookahead.peek(keywords::windows)
+                // A misspelled platform of a mapping arm, reported by `Platform::parse`.
+                || (input.peek(syn::Ident) && (input.peek2(Token![|]) || input.peek2(Token![=>])))
+            {
+                let span = input.span();
+                let mut platforms = vec![input.parse::<Platform>()?];
+                while input.peek(Token![|]) {
+                    input.parse::<Token![|]>()?;
+                    platforms.push(input.parse()?);
+                }
+                input.parse::<Token![=>]>()?;
+
+                paths.push(TableArm {
+                    span,
+                    platforms: Some(platforms),
+                    value: input.parse()?,
+                });
+            } else if lookahead.peek(keywords::fallback) {
+                input.parse::<keywords::fallback>()?;
+                input.parse::<Token![=]>()?;
+
+                let value_lookahead = input.lookahead1();
+                if value_lookahead.peek(LitStr) {
+                    fallback = Some(ModFallback::File(input.parse()?));
+                } else if value_lookahead.peek(keywords::error) {
+                    input.parse::<keywords::error>()?;
+                    fallback = Some(ModFallback::Error);
+                } else {
+                    return Err(value_lookahead.error());
+                }
+            } else {
+                return Ok(false);
+            }
+            Ok(true)
+        })?;
+
+        if common && layout != ModLayout::Dir {
+            return Err(Error::new(options.span, "`common` requires `layout = dir`"));
+        }
+        if layout == ModLayout::Dir && (platform_vis.is_some() || alias_vis.is_some()) {
+            return Err(Error::new(
+                options.span,
+                "`platform_vis` and `alias_vis` cannot be used with `layout = dir`: \
+                the logical module itself carries the declared visibility",
+            ));
+        }
+
+        Ok(Self {
+            options,
+            naming,
+            layout,
+            common,
+            platform_vis,
+            alias_vis,
+            fallback,
+            check_parity,
+            verify_files,
+            dir,
+            contract,
+            paths,
         })
     }
 }
 
-struct StructOptions {
+/// A single arm of a [`PlatformTable`]: `linux | macos => value` or `_ => value`.
+struct TableArm<T> {
+    span: Span2,
+    /// `None` for the wildcard arm `_`.
+    platforms: Option<Vec<Platform>>,
+    value: T,
+}
+
+/// An arm of a [`PlatformTable`] together with the platforms it applies to.
+struct ResolvedArm<'a, T> {
+    platforms: BTreeSet<Platform>,
+    /// Set for the `_` arm in the open world, which also applies to platforms other than Linux,
+    /// macOS and Windows.
+    others: bool,
+    value: &'a T,
+}
+
+/// A comma-separated list of arms mapping platforms to values
+/// (e.g., `linux | macos => i32, windows => *mut c_void`).
+///
+/// `include(...)` and `exclude(...)` may appear between the arms to narrow the platform set the
+/// table has to cover. It defaults to `all`.
+struct PlatformTable<T> {
     options: AttrOptions,
-    traits: Vec<syn::Path>,
+    arms: Vec<TableArm<T>>,
 }
 
-impl Parse for StructOptions {
+impl<T> PlatformTable<T> {
+    /// Assigns each arm the set of platforms it applies to.
+    ///
+    /// Fails if arms overlap, name platforms outside the table's platform set, or leave
+    /// platforms of that set uncovered.
+    fn resolve(&self) -> syn::Result<Vec<ResolvedArm<'_, T>>> {
+        let universe: BTreeSet<_> = self.options.allowed_set(|platform| platform);
+        if universe.is_empty() {
+            return Err(self.options.empty_set_error());
+        }
+
+        let mut errors = Vec::new();
+        let mut resolved = Vec::new();
+        let mut covered = BTreeSet::new();
+        let mut wildcard = None;
+
+        for (index, arm) in self.arms.iter().enumerate() {
+            let Some(ref platforms) = arm.platforms else {
+                if index + 1 != self.arms.len() {
+                    errors.push(Error::new(arm.span, "The `_` arm must be the last arm"));
+                }
+                wildcard = Some(arm);
+                continue;
+            };
+
+            let mut arm_set = BTreeSet::new();
+            for platform in platforms.iter().copied().flat_map(Platform::expand) {
+                if !universe.contains(&platform) {
+                    errors.push(Error::new(
+                        arm.span,
+                        format!(
+                            "`{}` is not part of the platform set of this table",
+                            platform.keyword()
+                        ),
+                    ));
+                } else if !covered.insert(platform) {
+                    errors.push(Error::new(
+                        arm.span,
+                        format!(
+                            "`{}` is already covered by a previous arm",
+                            platform.keyword()
+                        ),
+                    ));
+                } else {
+                    arm_set.insert(platform);
+                }
+            }
+            resolved.push(ResolvedArm {
+                platforms: arm_set,
+                others: false,
+                value: &arm.value,
+            });
+        }
+
+        let remaining = universe
+            .difference(&covered)
+            .copied()
+            .collect::<BTreeSet<_>>();
+        let others = self.options.includes_other_platforms();
+        match wildcard {
+            Some(arm) if remaining.is_empty() && !others => errors.push(Error::new(
+                arm.span,
+                "Unreachable `_` arm: all platforms are already covered",
+            )),
+            Some(arm) => resolved.push(ResolvedArm {
+                platforms: remaining,
+                others,
+                value: &arm.value,
+            }),
+            None if !remaining.is_empty() => {
+                let missing = remaining
+                    .iter()
+                    .map(|platform| format!("`{}`", platform.keyword()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                errors.push(Error::new(
+                    self.options.span,
+                    format!("Missing arm for {missing}: add an arm or a `_` fallback arm"),
+                ));
+            }
+            None => {}
+        }
+
+        match errors.into_iter().reduce(|mut combined, error| {
+            combined.combine(error);
+            combined
+        }) {
+            Some(error) => Err(error),
+            None => Ok(resolved),
+        }
+    }
+}
+
+impl<T> PlatformTable<T> {
+    /// Returns the warnings of the table options for its resolved `arms`.
+    #[must_use]
+    fn warnings(&self, arms: &[ResolvedArm<'_, T>]) -> TokenStream2 {
+        let cfgs = arms
+            .iter()
+            .map(|arm| {
+                format!(
+                    "#[cfg({})]",
+                    self.options.predicate(&arm.platforms, arm.others)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.options.warnings(&cfgs)
+    }
+}
+
+impl<T: Parse> PlatformTable<T> {
+    /// Parses a table whose arms use `S` (e.g., `=` instead of `=>`) to separate platforms and value.
+    fn parse_with_separator<S: Parse>(input: ParseStream<'_>) -> syn::Result<Self> {
+        let mut arms = Vec::new();
+        let options = parse_attributes(input, |input, _| {
+            let span = input.span();
+            let platforms = if input.peek(Token![_]) {
+                input.parse::<Token![_]>()?;
+                None
+            } else {
+                let mut platforms = vec![input.parse::<Platform>()?];
+                while input.peek(Token![|]) {
+                    input.parse::<Token![|]>()?;
+                    platforms.push(input.parse()?);
+                }
+                Some(platforms)
+            };
+
+            input.parse::<S>()?;
+            let value = input.parse()?;
+
+            arms.push(TableArm {
+                span,
+                platforms,
+                value,
+            });
+            Ok(true)
+        })?;
+
+        // Every arm already defines the item, documenting the others would define it twice.
+        if options.docs_all_platforms {
+            return Err(Error::new(
+                options.span,
+                "`docs_all_platforms` cannot be used with platform tables: \
+                use `doc_cfg` to annotate the documented arm",
+            ));
+        }
+
+        Ok(Self { options, arms })
+    }
+}
+
+impl<T: Parse> Parse for PlatformTable<T> {
     fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
-        parse_attributes(input, true)
+        Self::parse_with_separator::<Token![=>]>(input)
     }
 }
 
-fn parse_attributes(input: ParseStream<'_>, allow_traits: bool) -> syn::Result<StructOptions> {
-    let mut result = StructOptions {
-        options: AttrOptions {
-            span: input.span(),
-            exclude: HashSet::default(),
-            include: HashSet::default(),
-        },
-        traits: Vec::default(),
-    };
+/// A `const` or `static` declaration without a value (e.g., `pub const SIOCGIFCONF: u32;`).
+struct ValueDecl {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    /// `const` or `static` (including an optional `mut`).
+    kind: TokenStream2,
+    ident: proc_macro2::Ident,
+    ty: Type,
+}
+
+impl ValueDecl {
+    fn parse_const(input: ParseStream<'_>) -> syn::Result<Self> {
+        Self::parse_kind(input, |input| {
+            Ok(input.parse::<Token![const]>()?.to_token_stream())
+        })
+    }
+
+    fn parse_static(input: ParseStream<'_>) -> syn::Result<Self> {
+        Self::parse_kind(input, |input| {
+            let static_token = input.parse::<Token![static]>()?;
+            let mutability = input.parse::<Option<Token![mut]>>()?;
+            Ok(quote!(#static_token #mutability))
+        })
+    }
+
+    fn parse_kind<K: Fn(ParseStream<'_>) -> syn::Result<TokenStream2>>(
+        input: ParseStream<'_>,
+        kind: K,
+    ) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        let kind = kind(input)?;
+        let ident = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let ty = input.parse()?;
+
+        if input.peek(Token![=]) {
+            return Err(input.error(
+                "The value is taken from the attribute: remove the `= ...` from the declaration",
+            ));
+        }
+        input.parse::<Token![;]>()?;
+
+        Ok(Self {
+            attrs,
+            vis,
+            kind,
+            ident,
+            ty,
+        })
+    }
+}
+
+/// A type alias whose definition is selected per platform:
+/// `pub type RawHandle = { linux | macos => i32, windows => *mut c_void };`
+struct TypeTable {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    type_token: Token![type],
+    ident: proc_macro2::Ident,
+    generics: Generics,
+    table: PlatformTable<Type>,
+}
+
+impl Parse for TypeTable {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        let type_token = input.parse()?;
+        let ident = input.parse()?;
+        let generics = input.parse()?;
+        input.parse::<Token![=]>()?;
+
+        let content;
+        braced!(content in input);
+        let table = content.parse()?;
+
+        input.parse::<Token![;]>()?;
 
+        Ok(Self {
+            attrs,
+            vis,
+            type_token,
+            ident,
+            generics,
+            table,
+        })
+    }
+}
+
+/// Parses items of the same kind until the input is exhausted.
+fn parse_all<T: Parse>(input: ParseStream<'_>) -> syn::Result<Vec<T>> {
+    let mut items = Vec::new();
     while !input.is_empty() {
-        let lookahead = input.lookahead1();
+        items.push(input.parse()?);
+    }
+    Ok(items)
+}
 
-        if allow_traits && lookahead.peek(keywords::traits) {
-            input.parse::<keywords::traits>()?;
+/// Parses a comma-separated list of options.
+///
+/// `include(...)` and `exclude(...)` are handled here. Every other option is passed to `parse_extra`,
+/// which returns `false` if it does not know the option either.
+fn parse_attributes<F>(input: ParseStream<'_>, mut parse_extra: F) -> syn::Result<AttrOptions>
+where
+    F: FnMut(ParseStream<'_>, &Lookahead1<'_>) -> syn::Result<bool>,
+{
+    fn parse_spanned(input: ParseStream<'_>) -> syn::Result<(Span2, Platform)> {
+        Ok((input.span(), input.parse()?))
+    }
 
-            let content;
-            parenthesized!(content in input);
+    let mut options = AttrOptions {
+        span: input.span(),
+        exclude: HashSet::default(),
+        include: HashSet::default(),
+        lints: Vec::new(),
+        explain: None,
+        doc_cfg: false,
+        docs_all_platforms: false,
+        world: None,
+    };
+    let mut include_list = Vec::new();
+    let mut exclude_list = Vec::new();
+    let mut strict = false;
+
+    while !input.is_empty() {
+        let lookahead = input.lookahead1();
 
-            let traits = content.parse_terminated(syn::Path::parse, token::Comma)?;
-            result.traits.extend(traits);
-        } else if lookahead.peek(keywords::exclude) {
+        if lookahead.peek(keywords::exclude) {
             input.parse::<keywords::exclude>()?;
 
             let content;
             parenthesized!(content in input);
 
-            let platforms = content.parse_terminated(Platform::parse, token::Comma)?;
-            result.options.exclude.extend(platforms);
+            exclude_list.extend(content.parse_terminated(parse_spanned, token::Comma)?);
         } else if lookahead.peek(keywords::include) {
             input.parse::<keywords::include>()?;
 
             let content;
             parenthesized!(content in input);
 
-            let platforms = content.parse_terminated(Platform::parse, token::Comma)?;
-            result.options.include.extend(platforms);
-        } else {
+            include_list.extend(content.parse_terminated(parse_spanned, token::Comma)?);
+        } else if lookahead.peek(keywords::strict) {
+            input.parse::<keywords::strict>()?;
+            strict = true;
+        } else if lookahead.peek(keywords::explain) {
+            options.explain = Some(input.parse::<keywords::explain>()?.span);
+        } else if lookahead.peek(keywords::doc_cfg) {
+            input.parse::<keywords::doc_cfg>()?;
+            options.doc_cfg = true;
+        } else if lookahead.peek(keywords::docs_all_platforms) {
+            input.parse::<keywords::docs_all_platforms>()?;
+            options.docs_all_platforms = true;
+        } else if lookahead.peek(keywords::world) {
+            let span = input.parse::<keywords::world>()?.span;
+            input.parse::<Token![=]>()?;
+
+            let value_lookahead = input.lookahead1();
+            if value_lookahead.peek(keywords::open) {
+                input.parse::<keywords::open>()?;
+                options.world = Some((span, World::Open));
+            } else if value_lookahead.peek(keywords::closed) {
+                input.parse::<keywords::closed>()?;
+                options.world = Some((span, World::Closed));
+            } else {
+                return Err(value_lookahead.error());
+            }
+        } else if !parse_extra(input, &lookahead)? {
             return Err(lookahead.error());
         }
 
@@ -733,9 +3695,591 @@ fn parse_attributes(input: ParseStream<'_>, allow_traits: bool) -> syn::Result<S
         }
     }
 
-    if result.options.include.is_empty() {
-        result.options.include.insert(Platform::All);
+    let lints = platform_list_lints(&include_list, &exclude_list);
+    if strict {
+        if let Some(error) = lints
+            .into_iter()
+            .map(|(span, message)| Error::new(span, message))
+            .reduce(|mut combined, error| {
+                combined.combine(error);
+                combined
+            })
+        {
+            return Err(error);
+        }
+    } else {
+        options.lints = lints;
+    }
+
+    options
+        .include
+        .extend(include_list.into_iter().map(|(_, platform)| platform));
+    options
+        .exclude
+        .extend(exclude_list.into_iter().map(|(_, platform)| platform));
+    if options.include.is_empty() {
+        options.include.insert(Platform::All);
+    }
+
+    Ok(options)
+}
+
+/// Collects redundant and contradictory entries of `include(...)` and `exclude(...)`.
+#[must_use]
+fn platform_list_lints(
+    include: &[(Span2, Platform)],
+    exclude: &[(Span2, Platform)],
+) -> Vec<(Span2, String)> {
+    let mut lints = Vec::new();
+
+    for (list, name) in [(include, "include"), (exclude, "exclude")] {
+        for (index, &(span, platform)) in list.iter().enumerate() {
+            let expanded = platform.expand();
+            let covering_group = list.iter().find(|&&(_, other)| {
+                other != platform && expanded.iter().all(|part| other.expand().contains(part))
+            });
+
+            if list[..index].iter().any(|&(_, other)| other == platform) {
+                lints.push((
+                    span,
+                    format!(
+                        "`{}` is listed more than once in `{name}(...)`",
+                        platform.keyword()
+                    ),
+                ));
+            } else if let Some(&(_, group)) = covering_group {
+                lints.push((
+                    span,
+                    format!(
+                        "`{}` is redundant in `{name}(...)`: it is already part of `{}`",
+                        platform.keyword(),
+                        group.keyword()
+                    ),
+                ));
+            }
+        }
+    }
+
+    for &(span, platform) in include {
+        if platform == Platform::All {
+            lints.push((
+                span,
+                "`include(all)` is the default and can be omitted".to_owned(),
+            ));
+        }
+    }
+
+    let included = if include.is_empty() {
+        Platform::All.expand()
+    } else {
+        include
+            .iter()
+            .flat_map(|&(_, platform)| platform.expand())
+            .collect()
+    };
+    for &(span, platform) in exclude {
+        if !platform.expand().iter().any(|part| included.contains(part)) {
+            lints.push((
+                span,
+                format!(
+                    "`{}` is excluded but was never included",
+                    platform.keyword()
+                ),
+            ));
+        }
+    }
+
+    lints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forwarder_attr_error_rejects_symbol_attributes() {
+        let sys_ident = format_ident!("run_impl");
+        let attrs: Vec<Attribute> = parse_quote! {
+            #[no_mangle]
+            #[unsafe(export_name = "run")]
+            #[test]
+        };
+
+        for attr in &attrs {
+            let error = forwarder_attr_error(attr, &sys_ident).expect("attribute must be rejected");
+            assert!(
+                error.to_string().contains("only forwards to `run_impl`"),
+                "{error}"
+            );
+        }
+    }
+
+    #[test]
+    fn forwarder_attr_error_forwards_other_attributes() {
+        let sys_ident = format_ident!("run_impl");
+        let attrs: Vec<Attribute> = parse_quote! {
+            #[inline]
+            #[must_use]
+            #[doc = "Runs."]
+            #[unsafe(no_sanitize)]
+        };
+
+        for attr in &attrs {
+            assert!(forwarder_attr_error(attr, &sys_ident).is_none());
+        }
+    }
+
+    fn expand_inline_blocks(module: ItemMod) -> syn::Result<String> {
+        let options = syn::parse_str::<AttrOptions>("")?;
+        let allowed_set = options.allowed_set(|platform| platform);
+        let items = module.content.map(|(_, items)| items).unwrap_or_default();
+
+        inline_platform_blocks(items, &allowed_set, &options).map(|output| output.to_string())
+    }
+
+    #[test]
+    fn inline_blocks_reject_overlap_with_ungated_blocks() {
+        let module: ItemMod = parse_quote! {
+            mod imp {
+                #[all]
+                mod imp {}
+                #[cfg(feature = "x")]
+                #[linux]
+                mod imp {}
+            }
+        };
+
+        let error = expand_inline_blocks(module).unwrap_err();
+        assert!(
+            error.to_string().contains("`linux` is already covered"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn inline_blocks_warn_about_partial_coverage() {
+        let module: ItemMod = parse_quote! {
+            mod imp {
+                #[cfg(feature = "x")]
+                #[all]
+                mod imp {}
+            }
+        };
+
+        let output = expand_inline_blocks(module).unwrap();
+        assert!(
+            output.contains("No platform block for linux, macos, windows"),
+            "{output}"
+        );
+    }
+
+    #[test]
+    fn inline_blocks_accept_complementary_cfgs() {
+        let module: ItemMod = parse_quote! {
+            mod imp {
+                #[cfg(feature = "x")]
+                #[all]
+                mod imp {}
+                #[cfg(not(feature = "x"))]
+                #[posix]
+                mod imp {}
+                #[cfg(not(feature = "x"))]
+                #[windows]
+                mod imp {}
+            }
+        };
+
+        let output = expand_inline_blocks(module).unwrap();
+        assert!(!output.contains("deprecated"), "{output}");
+    }
+
+    #[test]
+    fn cfg_parses_boolean_literals() {
+        let cfg = syn::parse_str::<Cfg>("all(true, unix)").unwrap();
+        assert_eq!(cfg.to_string(), "unix");
+
+        let cfg = syn::parse_str::<Cfg>("any(false, not(true))").unwrap();
+        assert!(cfg == Cfg::FALSE, "{cfg}");
+    }
+
+    #[test]
+    fn cfg_keeps_unknown_predicates_verbatim() {
+        let cfg = syn::parse_str::<Cfg>(r#"all(version("1.80"), unix, unix)"#).unwrap();
+        assert_eq!(cfg.to_string(), r#"all(unix, version ("1.80"))"#);
+        assert_eq!(
+            cfg.to_token_stream().to_string(),
+            r#"all (unix , version ("1.80"))"#
+        );
+    }
+
+    #[test]
+    fn inline_blocks_accept_boolean_cfgs() {
+        let module: ItemMod = parse_quote! {
+            mod imp {
+                #[cfg(false)]
+                #[linux]
+                mod imp {}
+                #[cfg(true)]
+                #[all]
+                mod imp {}
+            }
+        };
+
+        let output = expand_inline_blocks(module).unwrap();
+        assert!(!output.contains("deprecated"), "{output}");
+    }
+
+    fn lint_messages(options: &str) -> Vec<String> {
+        let options = syn::parse_str::<AttrOptions>(options).unwrap();
+        options
+            .lints
+            .into_iter()
+            .map(|(_, message)| message)
+            .collect()
+    }
+
+    #[test]
+    fn platform_list_lints_report_redundant_entries() {
+        assert_eq!(
+            lint_messages("include(linux, linux)"),
+            ["`linux` is listed more than once in `include(...)`"]
+        );
+        assert_eq!(
+            lint_messages("include(posix, linux)"),
+            ["`linux` is redundant in `include(...)`: it is already part of `posix`"]
+        );
+        assert_eq!(
+            lint_messages("include(linux), exclude(windows)"),
+            ["`windows` is excluded but was never included"]
+        );
+        assert_eq!(
+            lint_messages("include(all)"),
+            ["`include(all)` is the default and can be omitted"]
+        );
+        assert!(lint_messages("include(posix), exclude(macos)").is_empty());
+    }
+
+    #[test]
+    fn strict_turns_lints_into_errors() {
+        let error = syn::parse_str::<AttrOptions>("include(linux, linux), strict")
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "`linux` is listed more than once in `include(...)`"
+        );
+        assert!(syn::parse_str::<AttrOptions>("include(linux), strict").is_ok());
+    }
+
+    /// Returns the `explain:` warnings and the doc lines of an item with `options`.
+    fn explained(options: &str) -> (Vec<String>, Vec<String>) {
+        fn string_literals(tokens: TokenStream2, literals: &mut Vec<String>) {
+            for token in tokens {
+                match token {
+                    proc_macro2::TokenTree::Group(group) => {
+                        string_literals(group.stream(), literals)
+                    }
+                    proc_macro2::TokenTree::Literal(literal) => {
+                        if let Ok(literal) = syn::parse2::<LitStr>(literal.into_token_stream()) {
+                            literals.push(literal.value());
+                        }
+                    }
+                    proc_macro2::TokenTree::Ident(_) | proc_macro2::TokenTree::Punct(_) => {}
+                }
+            }
+        }
+
+        let options = syn::parse_str::<AttrOptions>(options).unwrap();
+        let (warnings, attrs) = options.explain_item();
+        let mut notes = Vec::new();
+        string_literals(warnings, &mut notes);
+        let docs = attrs
+            .iter()
+            .map(|attr| match attr.meta {
+                Meta::NameValue(ref doc) => doc.value.to_token_stream().to_string(),
+                Meta::Path(_) | Meta::List(_) => panic!("not a doc attribute"),
+            })
+            .collect();
+        (notes, docs)
+    }
+
+    #[test]
+    fn explain_item_describes_closed_world() {
+        let (notes, docs) = explained("include(posix), explain");
+        assert_eq!(
+            notes,
+            [concat!(
+                "explain: include(posix) -> {linux, macos}, exclude() -> {}, platforms {linux, macos}, ",
+                r#"generated #[cfg(any(target_os = "linux", target_os = "macos"))]"#
+            )]
+        );
+        assert_eq!(docs, [r#""""#, r#""Available on: Linux, macOS""#]);
+    }
+
+    #[test]
+    fn explain_item_describes_open_world() {
+        let (notes, docs) = explained("exclude(windows), world = open, explain");
+        assert_eq!(
+            notes,
+            [concat!(
+                "explain: include(all) -> {linux, macos, windows}, exclude(windows) -> {windows}, ",
+                r#"platforms {linux, macos, ...}, generated #[cfg(not(target_os = "windows"))]"#
+            )]
+        );
+        assert_eq!(
+            docs,
+            [r#""""#, r#""Available on: Linux, macOS, other platforms""#]
+        );
+    }
+
+    #[test]
+    fn explain_item_is_silent_without_explain() {
+        assert_eq!(explained("include(posix)"), (Vec::new(), Vec::new()));
+    }
+
+    fn generated_predicate(options: &str) -> String {
+        let options = syn::parse_str::<AttrOptions>(options).unwrap();
+        let platforms = options.allowed_set(|platform| platform);
+        options
+            .predicate(&platforms, options.includes_other_platforms())
+            .to_string()
+    }
+
+    #[test]
+    fn open_world_complements_exclusions() {
+        assert_eq!(
+            generated_predicate("exclude(windows), world = open"),
+            r#"not(target_os = "windows")"#
+        );
+        assert_eq!(generated_predicate("world = open"), "all()");
+        // Specific platforms stay specific.
+        assert_eq!(
+            generated_predicate("include(posix), world = open"),
+            r#"any(target_os = "linux", target_os = "macos")"#
+        );
+    }
+
+    #[test]
+    fn closed_world_is_the_default() {
+        let closed = r#"any(target_os = "linux", target_os = "macos")"#;
+        assert_eq!(generated_predicate("exclude(windows)"), closed);
+        assert_eq!(
+            generated_predicate("exclude(windows), world = closed"),
+            closed
+        );
+    }
+
+    #[test]
+    fn open_world_tables_cover_other_platforms() {
+        let table =
+            syn::parse_str::<PlatformTable<Expr>>("world = open, posix => 1, _ => 2").unwrap();
+        let predicates = table
+            .resolve()
+            .unwrap()
+            .iter()
+            .map(|arm| {
+                table
+                    .options
+                    .predicate(&arm.platforms, arm.others)
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            predicates,
+            [
+                r#"any(target_os = "linux", target_os = "macos")"#,
+                r#"not(any(target_os = "linux", target_os = "macos"))"#,
+            ]
+        );
+    }
+
+    /// Evaluates `cfg` on a target that only sets `target_os`.
+    fn holds_on(cfg: &Cfg, target_os: &str) -> bool {
+        match *cfg {
+            Cfg::Name(_) | Cfg::Verbatim(_) => false,
+            Cfg::KeyValue(ref key, ref value) => key == "target_os" && value == target_os,
+            Cfg::Not(ref inner) => !holds_on(inner, target_os),
+            Cfg::All(ref terms) => terms.iter().all(|term| holds_on(term, target_os)),
+            Cfg::Any(ref terms) => terms.iter().any(|term| holds_on(term, target_os)),
+        }
+    }
+
+    #[test]
+    fn supported_platforms_rejects_other_targets() {
+        let platforms = [Platform::Linux, Platform::Macos].into_iter().collect();
+        let output = expand_supported_platforms(&platforms, "crate `demo`");
+        let error: syn::ItemMacro = syn::parse2(output).unwrap();
+
+        let cfg = error.attrs[0].parse_args::<Cfg>().unwrap();
+        assert!(holds_on(&cfg, "freebsd"));
+        assert!(holds_on(&cfg, "windows"));
+        assert!(!holds_on(&cfg, "linux"));
+        assert!(!holds_on(&cfg, "macos"));
+
+        let message = error.mac.parse_body::<LitStr>().unwrap().value();
+        assert_eq!(
+            message,
+            "crate `demo` does not support this target (supported: linux, macos)"
+        );
+    }
+
+    #[test]
+    fn unsupported_target_error_is_empty_when_everything_is_supported() {
+        assert!(unsupported_target_error(&Cfg::TRUE, "unreachable").is_empty());
+    }
+
+    #[test]
+    fn source_dirs_treat_crate_roots_as_module_roots() {
+        let crate_names = ["tool".to_owned()];
+        for root in ["src/lib.rs", "src/sys/mod.rs", "src/bin/tool.rs"] {
+            let dirs = SourceDirs::for_file(Path::new(root), &crate_names);
+            assert_eq!(dirs.module_dir, dirs.file_dir, "{root}");
+        }
+
+        let dirs = SourceDirs::for_file(Path::new("src/bin/helper.rs"), &crate_names);
+        assert_eq!(dirs.file_dir, Path::new("src/bin"));
+        assert_eq!(dirs.module_dir, Path::new("src/bin/helper"));
+    }
+
+    /// Runs `verify_files` for a module `driver` on the fixtures in `tests/verify`, which only
+    /// contain `driver_linux.rs` and `driver_macos.rs`.
+    fn verify_fixtures(options: &str) -> syn::Result<String> {
+        let options = syn::parse_str::<ModOptions>(options)?;
+        let platforms = options.options.allowed_set(|platform| platform);
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/verify");
+        let dirs = SourceDirs {
+            file_dir: dir.clone(),
+            module_dir: dir,
+        };
+
+        options
+            .verify_files(
+                &format_ident!("driver"),
+                &platforms,
+                &dirs,
+                Span2::call_site(),
+            )
+            .map(|warnings| warnings.to_string())
+    }
+
+    #[test]
+    fn verify_files_reports_missing_files() {
+        let error = verify_fixtures(r#"naming = "{name}_{platform}", include(linux, windows)"#)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "The file `tests/verify/driver_windows.rs` of `driver` does not exist"
+        );
+    }
+
+    #[test]
+    fn verify_files_warns_about_unused_files() {
+        let warnings = verify_fixtures(r#"naming = "{name}_{platform}", include(linux)"#).unwrap();
+        assert!(
+            warnings.contains(
+                "`tests/verify/driver_macos.rs` is never used: `macos` is not part of the platform set of `driver`"
+            ),
+            "{warnings}"
+        );
+        assert!(!warnings.contains("driver_windows.rs"), "{warnings}");
+    }
+
+    #[test]
+    fn edit_distance_counts_single_character_edits() {
+        assert_eq!(edit_distance("linux", "linux"), 0);
+        assert_eq!(edit_distance("linx", "linux"), 1);
+        assert_eq!(edit_distance("windwos", "windows"), 2);
+        assert_eq!(edit_distance("", "macos"), 5);
+    }
+
+    #[test]
+    fn unknown_platforms_suggest_the_closest_keyword() {
+        let message = |input: &str| syn::parse_str::<Platform>(input).err().unwrap().to_string();
+
+        assert!(message("linx").contains("did you mean `linux`?"));
+        assert!(message("mac").contains("did you mean `macos`?"));
+        let unrelated = message("freebsd");
+        assert!(
+            unrelated.starts_with("unknown platform `freebsd`"),
+            "{unrelated}"
+        );
+        assert!(!unrelated.contains("did you mean"), "{unrelated}");
+    }
+
+    #[test]
+    fn misspelled_mapping_arms_suggest_the_closest_keyword() {
+        let error = syn::parse_str::<ModOptions>(r#"linx | macos => "unix.rs""#)
+            .err()
+            .unwrap();
+        assert!(
+            error.to_string().contains("did you mean `linux`?"),
+            "{error}"
+        );
+    }
+
+    fn parity_fixtures(names: &[&str]) -> Vec<PathBuf> {
+        names
+            .iter()
+            .map(|name| {
+                Path::new(env!("CARGO_MANIFEST_DIR"))
+                    .join("tests/parity")
+                    .join(name)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn public_api_ignores_parameter_names() {
+        let linux: syn::File = parse_quote! {
+            pub fn read(buf: &mut [u8]) -> usize { 0 }
+            pub struct Device;
+            impl Device {
+                pub fn reset(&mut self, (code, _): (u8, u8)) {}
+            }
+        };
+        let windows: syn::File = parse_quote! {
+            pub fn read(b: &mut [u8]) -> usize { b.len() }
+            pub struct Device;
+            impl Device {
+                pub fn reset(&mut self, _pair: (u8, u8)) {}
+            }
+        };
+
+        assert_eq!(public_api(&linux.items), public_api(&windows.items));
+    }
+
+    #[test]
+    fn public_api_compares_types() {
+        let linux: syn::File = parse_quote!(
+            pub fn read(buf: &mut [u8]) -> usize {
+                0
+            }
+        );
+        let windows: syn::File = parse_quote!(
+            pub fn read(buf: &mut [u16]) -> usize {
+                0
+            }
+        );
+
+        assert_ne!(public_api(&linux.items), public_api(&windows.items));
+    }
+
+    #[test]
+    fn check_parity_accepts_renamed_parameters() {
+        let files = parity_fixtures(&["renamed_linux.rs", "renamed_windows.rs"]);
+        let output = check_parity(&files, Span2::call_site()).to_string();
+
+        assert!(!output.contains("compile_error"), "{output}");
     }
 
-    Ok(result)
+    #[test]
+    fn check_parity_reports_mismatches() {
+        let files = parity_fixtures(&["mismatch_linux.rs", "mismatch_windows.rs"]);
+        let output = check_parity(&files, Span2::call_site()).to_string();
+
+        assert!(output.contains("is missing `Device::reset`"), "{output}");
+        assert!(output.contains("`read` differs between"), "{output}");
+    }
 }
@@ -1,5 +1,7 @@
-use platify::{sys_function, sys_struct};
+use platify::{sys_const, sys_function, sys_static, sys_struct, sys_type};
 use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
 
 // =========================================================================
 // TEST: Basics & Dispatching
@@ -156,6 +158,112 @@ async fn test_async() {
     assert_eq!(worker.fetch().await, 42);
 }
 
+// =========================================================================
+// TEST: Attribute Handling
+// Checks that attributes are forwarded to the wrapper and that a user `#[cfg]`
+// removes the declaration before the macro runs.
+// =========================================================================
+
+struct CallerTracker;
+
+impl CallerTracker {
+    #[sys_function]
+    #[track_caller]
+    #[must_use]
+    fn caller_line(&self) -> u32;
+
+    #[track_caller]
+    fn caller_line_impl(&self) -> u32 {
+        std::panic::Location::caller().line()
+    }
+
+    // Disabled by the user's cfg: if it were ignored, the wrapper would clash
+    // with the method of the same name below.
+    #[sys_function]
+    #[cfg(any())]
+    fn disabled_line(&self) -> u32;
+}
+
+impl CallerTracker {
+    fn disabled_line(&self) -> u32 {
+        0
+    }
+}
+
+#[test]
+fn test_attribute_handling() {
+    let tracker = CallerTracker;
+    assert_eq!(tracker.caller_line(), line!());
+    assert_eq!(tracker.disabled_line(), 0);
+}
+
+// =========================================================================
+// TEST: Merged cfg Attributes
+// Checks that `#[cfg]`s on the item also gate everything platify generates.
+// If they did not, the aliases and assertions below would refer to a type
+// that does not exist.
+// =========================================================================
+
+#[sys_struct(traits(Send))]
+#[cfg(any())]
+struct NeverCompiled(Rc<u8>);
+
+trait Disabled {
+    #[platify::sys_trait_function]
+    #[cfg(any())]
+    fn never_declared(&self) -> NeverCompiled;
+
+    #[platify::sys_trait_function]
+    #[cfg(test)]
+    fn declared(&self) -> u8 {
+        7
+    }
+}
+
+impl Disabled for CallerTracker {}
+
+#[test]
+fn test_merged_cfgs() {
+    assert_eq!(CallerTracker.declared(), 7);
+}
+
+// =========================================================================
+// TEST: Required Trait Methods for Documentation
+// Checks that `docs_all_platforms` keeps required methods required on their
+// platforms. Under `cfg(doc)`, the other platforms get a default body, so the
+// implementation below also compiles for `cargo doc` on every platform.
+// =========================================================================
+
+trait NativeHandle {
+    #[platify::sys_trait_function(include(posix), docs_all_platforms)]
+    fn fd(&self) -> i32;
+
+    #[platify::sys_trait_function(include(windows), docs_all_platforms)]
+    fn handle(&self) -> usize;
+}
+
+struct Handle;
+
+impl NativeHandle for Handle {
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    fn fd(&self) -> i32 {
+        3
+    }
+
+    #[cfg(target_os = "windows")]
+    fn handle(&self) -> usize {
+        4
+    }
+}
+
+#[test]
+fn test_required_methods_for_documentation() {
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    assert_eq!(Handle.fd(), 3);
+    #[cfg(target_os = "windows")]
+    assert_eq!(Handle.handle(), 4);
+}
+
 // =========================================================================
 // TEST: Complex Exclusion Logic
 // Checks if the exclusion logic works (compile-time check via cfg).
@@ -187,6 +295,48 @@ fn test_exclusion() {
     // Calling it would result in a compile error, which proves the macro works.
 }
 
+// =========================================================================
+// TEST: Open World
+// Checks `world = open`, where exclusions keep platforms other than Linux,
+// macOS and Windows. A `_` arm after all known platforms is then reachable.
+// =========================================================================
+
+impl OsSpecific {
+    // Exists everywhere EXCEPT on Windows, including e.g. FreeBSD.
+    #[sys_function(exclude(windows), world = open)]
+    fn not_windows(&self) -> bool;
+
+    #[allow(dead_code)]
+    fn not_windows_impl(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn test_open_world() {
+    #[cfg(not(windows))]
+    assert!(OsSpecific.not_windows());
+
+    let known = platify::select! {
+        world = open,
+        posix | windows => true,
+        _ => false,
+    };
+    assert!(known);
+}
+
+// =========================================================================
+// TEST: Supported Platforms
+// Declaring every platform must not produce an error on the test target.
+// =========================================================================
+
+platify::supported_platforms!(linux, macos, windows);
+
+#[test]
+fn test_supported_platforms() {
+    platify::supported_platforms!(posix, windows);
+}
+
 // =========================================================================
 // TEST: Trait Assertions with Generics
 // Verifies that 'traits(...)' works correctly with generic structs.
@@ -195,6 +345,7 @@ fn test_exclusion() {
 // =========================================================================
 
 #[sys_struct(traits(Send, Sync))]
+#[allow(dead_code)]
 struct GenericWrapper<T: Clone + Send + Sync> {
     data: T,
 }
@@ -208,6 +359,7 @@ fn test_generic_trait_assertion() {
 }
 
 #[sys_struct(traits(Send))]
+#[allow(dead_code)]
 struct UnsizedWrapper<T: ?Sized + Send> {
     data: Box<T>,
 }
@@ -258,3 +410,368 @@ fn test_lifetimes() {
     let data = "  hello  ";
     assert_eq!(parser.parse(data), "hello");
 }
+
+// =========================================================================
+// TEST: Trait Assertions for Concrete Instantiations
+// Verifies that 'traits(Trait for Type)' only checks the given instantiation.
+// `SharedHandle<Rc<u8>>` is neither Send nor Sync, so a generic check would fail.
+// =========================================================================
+
+#[sys_struct(traits(Send for SharedHandle<u8>, Sync for SharedHandle<Arc<u8>>))]
+struct SharedHandle<T> {
+    data: T,
+}
+
+#[test]
+fn test_concrete_trait_assertion() {
+    // Compile-time check: the struct must still be usable with non-Send types.
+    let handle = SharedHandle {
+        data: Rc::new(1_u8),
+    };
+    assert_eq!(*handle.data, 1);
+}
+
+// =========================================================================
+// TEST: Enums & Unions
+// Checks that #[sys_struct] accepts enums and unions and generates the
+// platform-specific aliases (e.g., `StateLinux`) for them as well.
+// =========================================================================
+
+#[sys_struct(traits(Send, Sync, Copy))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum State {
+    Idle,
+    Running(u32),
+}
+
+#[sys_struct(traits(Copy))]
+#[derive(Clone, Copy)]
+union RawValue {
+    int: u32,
+    float: f32,
+}
+
+#[test]
+fn test_enum_and_union() {
+    #[cfg(target_os = "linux")]
+    let state: StateLinux = State::Running(1);
+    #[cfg(target_os = "macos")]
+    let state: StateMacos = State::Running(1);
+    #[cfg(target_os = "windows")]
+    let state: StateWindows = State::Running(1);
+    assert_ne!(state, State::Idle);
+
+    let value = RawValue { int: 0 };
+    assert_eq!(unsafe { value.float }, 0.0);
+}
+
+// =========================================================================
+// TEST: Platform-Selected Type Aliases
+// Checks that sys_type! generates exactly one alias per platform, including
+// generic aliases and the `_` fallback arm.
+// =========================================================================
+
+sys_type! {
+    /// Differs in size between the platforms.
+    type NativeInt = {
+        linux | macos => i32,
+        windows => i64,
+    };
+
+    // `doc_cfg` annotates every alias with its platforms.
+    type Collection<T> = { doc_cfg, posix => Vec<T>, _ => Option<T> };
+}
+
+#[test]
+fn test_sys_type() {
+    #[cfg(unix)]
+    assert_eq!(std::mem::size_of::<NativeInt>(), 4);
+    #[cfg(windows)]
+    assert_eq!(std::mem::size_of::<NativeInt>(), 8);
+
+    let _collection: Collection<u8> = Default::default();
+}
+
+// =========================================================================
+// TEST: Per-Platform Constants & Statics
+// Checks that sys_const / sys_static generate one value per platform with
+// the declared type.
+// =========================================================================
+
+#[sys_const(doc_cfg, linux = 1, macos = 2, windows = 3)]
+const PLATFORM_ID: u8;
+
+#[sys_static(windows = "\\", _ = "/")]
+static SEPARATOR: &str;
+
+#[test]
+fn test_sys_const_and_static() {
+    #[cfg(target_os = "linux")]
+    assert_eq!(PLATFORM_ID, 1);
+    #[cfg(target_os = "macos")]
+    assert_eq!(PLATFORM_ID, 2);
+    #[cfg(target_os = "windows")]
+    assert_eq!(PLATFORM_ID, 3);
+
+    assert_eq!(SEPARATOR, std::path::MAIN_SEPARATOR_STR);
+}
+
+// =========================================================================
+// TEST: Expression-Level Selection
+// Checks that select! only compiles the arm of the current platform.
+// The other arms reference items which do not exist on this platform.
+// =========================================================================
+
+#[test]
+fn test_select() {
+    let name = platify::select! {
+        linux => { LinuxOnly::NAME },
+        macos => "macos",
+        windows => "windows",
+    };
+    assert_eq!(name, std::env::consts::OS);
+
+    let family = platify::select! { posix => "unix", _ => "windows" };
+    assert_eq!(family, std::env::consts::FAMILY);
+}
+
+#[cfg(target_os = "linux")]
+struct LinuxOnly;
+
+#[cfg(target_os = "linux")]
+impl LinuxOnly {
+    const NAME: &'static str = "linux";
+}
+
+// =========================================================================
+// TEST: Platform Modules with Naming Templates
+// Checks that several #[platform_mod] declarations can coexist in the same
+// parent module when their platform modules are named by a template.
+// =========================================================================
+
+mod platform_modules {
+    use platify::platform_mod;
+
+    // Backed by `platform_modules/driver_linux.rs`, ...
+    // `check_parity` verifies that all three files export the same API, `verify_files` that they exist.
+    #[platform_mod(
+        naming = "{name}_{platform}",
+        check_parity,
+        verify_files,
+        dir = "tests/platform_modules"
+    )]
+    pub use driver;
+
+    // The files do not exist, but the user's `#[cfg]` disables the whole module.
+    #[platform_mod(naming = "{name}_{platform}")]
+    #[cfg(any())]
+    pub use missing;
+
+    // Platform blocks with their own `#[cfg]` may be alternatives of each other.
+    #[platform_mod]
+    pub mod build_kind {
+        #[cfg(test)]
+        #[all]
+        mod imp {
+            pub const KIND: &str = "test";
+        }
+
+        #[cfg(not(test))]
+        #[all]
+        mod imp {
+            pub const KIND: &str = "regular";
+        }
+    }
+
+    // Backed by `platform_modules/net/linux.rs`, ...
+    #[platform_mod(naming = "{name}/{platform}")]
+    pub use net;
+
+    // A real `storage` module including `platform_modules/storage/linux.rs`, ...
+    // and the shared `platform_modules/storage/common.rs`.
+    #[platform_mod(layout = dir, common)]
+    pub use storage;
+
+    // The logical alias `api` is re-exported publicly instead of being private.
+    #[platform_mod(naming = "{name}_{platform}", alias_vis = pub)]
+    pub use api;
+
+    // Only Windows is supported; every other platform gets the stub module.
+    #[platform_mod(
+        naming = "{name}_{platform}",
+        include(windows),
+        fallback = "sensor_unsupported.rs",
+        verify_files,
+        dir = "tests/platform_modules"
+    )]
+    pub use sensor;
+
+    // The fallback file has the same stem as `sensor`'s, the stub module is named `camera_fallback`.
+    #[platform_mod(
+        naming = "{name}_{platform}",
+        include(windows),
+        fallback = "fallback/sensor_unsupported.rs"
+    )]
+    pub use camera;
+
+    // Linux and macOS share `platform_modules/shell_unix.rs`, Windows uses `shell_win32.rs`.
+    #[platform_mod(
+        linux | macos => "shell_unix.rs",
+        windows => "shell_win32.rs",
+        check_parity,
+        dir = "tests/platform_modules"
+    )]
+    pub use shell;
+
+    pub trait PlatformInfo {
+        fn name() -> &'static str;
+    }
+
+    // Every `info_*.rs` must provide `Imp: PlatformInfo`; also generates `pub type Info = info::Imp;`.
+    #[platform_mod(naming = "{name}_{platform}", contract = PlatformInfo)]
+    pub use info;
+
+    // Raw identifiers: backed by `type_linux.rs`, ..., and generates `pub type Type = r#type::Imp;`.
+    #[platform_mod(
+        naming = "{name}_{platform}",
+        contract = PlatformInfo,
+        verify_files,
+        dir = "tests/platform_modules"
+    )]
+    pub use r#type;
+
+    // Inline module: the marked blocks are cfg'd and re-exported, `shared` exists everywhere.
+    // `docs_all_platforms` additionally documents the other platforms' blocks.
+    #[platform_mod(docs_all_platforms)]
+    pub mod console {
+        pub fn shared() -> bool {
+            true
+        }
+
+        #[posix]
+        mod imp {
+            pub fn newline() -> &'static str {
+                "\n"
+            }
+        }
+
+        #[windows]
+        mod imp {
+            pub fn newline() -> &'static str {
+                "\r\n"
+            }
+        }
+    }
+
+    // Hand-written platform modules, aliased through `use` paths below.
+    #[allow(dead_code)]
+    pub mod sys {
+        pub mod linux {
+            pub mod platform {
+                pub const NAME: &str = "linux";
+            }
+            pub struct Error;
+        }
+        pub mod macos {
+            pub mod platform {
+                pub const NAME: &str = "macos";
+            }
+            pub struct Error;
+        }
+        pub mod windows {
+            pub mod platform {
+                pub const NAME: &str = "windows";
+            }
+            pub struct Error;
+        }
+    }
+
+    // Resolves to `self::sys::linux::platform`, ... and `self::sys::linux::{self as os, Error}`, ...
+    #[platform_mod]
+    pub use self::sys::platform as os_platform;
+
+    #[platform_mod]
+    pub use self::sys::{self as os, Error as OsError};
+
+    pub fn platforms() -> (&'static str, &'static str) {
+        (driver::platform(), net::platform())
+    }
+
+    pub fn line_ending() -> &'static str {
+        shell::line_ending()
+    }
+
+    pub fn sensor_supported() -> bool {
+        sensor::SUPPORTED
+    }
+
+    pub fn camera_supported() -> bool {
+        camera::SUPPORTED
+    }
+}
+
+#[test]
+fn test_platform_mod_naming() {
+    let os = std::env::consts::OS;
+    assert_eq!(platform_modules::platforms(), (os, os));
+}
+
+#[test]
+fn test_platform_mod_dir_layout() {
+    // `storage` is a real (public) module, so it is reachable from outside.
+    assert_eq!(platform_modules::storage::platform(), std::env::consts::OS);
+    assert!(platform_modules::storage::shared());
+}
+
+// Without `dir`, the files are located next to this crate root (`tests/integration.rs`),
+// i.e. in `tests/local_files/linux.rs`, ...
+#[cfg(feature = "local-file")]
+#[platify::platform_mod(layout = dir, verify_files, check_parity)]
+use local_files;
+
+#[cfg(feature = "local-file")]
+#[test]
+fn test_platform_mod_local_file() {
+    assert_eq!(local_files::NAME, std::env::consts::OS);
+}
+
+#[test]
+fn test_platform_mod_public_alias() {
+    assert_eq!(platform_modules::api::platform(), std::env::consts::OS);
+}
+
+#[test]
+fn test_platform_mod_fallback() {
+    assert_eq!(platform_modules::sensor_supported(), cfg!(windows));
+    assert_eq!(platform_modules::camera_supported(), cfg!(windows));
+}
+
+#[test]
+fn test_platform_mod_contract() {
+    use platform_modules::PlatformInfo as _;
+
+    assert_eq!(platform_modules::Info::name(), std::env::consts::OS);
+    assert_eq!(platform_modules::Type::name(), std::env::consts::OS);
+}
+
+#[test]
+fn test_platform_mod_inline() {
+    let expected = if cfg!(windows) { "\r\n" } else { "\n" };
+    assert_eq!(platform_modules::console::newline(), expected);
+    assert!(platform_modules::console::shared());
+    assert_eq!(platform_modules::build_kind::KIND, "test");
+}
+
+#[test]
+fn test_platform_mod_use_paths() {
+    let os = std::env::consts::OS;
+    assert_eq!(platform_modules::os_platform::NAME, os);
+    assert_eq!(platform_modules::os::platform::NAME, os);
+    let _error: platform_modules::OsError = platform_modules::os::Error;
+}
+
+#[test]
+fn test_platform_mod_path_mapping() {
+    let expected = if cfg!(windows) { "\r\n" } else { "\n" };
+    assert_eq!(platform_modules::line_ending(), expected);
+}
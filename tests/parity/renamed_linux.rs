@@ -0,0 +1,3 @@
+pub fn read(buf: &mut [u8]) -> usize {
+    buf.len()
+}
@@ -0,0 +1,3 @@
+pub fn read(b: &mut [u8]) -> usize {
+    b.len()
+}
@@ -0,0 +1 @@
+pub const SUPPORTED: bool = false;
@@ -0,0 +1,3 @@
+pub fn line_ending() -> &'static str {
+    "\n"
+}
@@ -0,0 +1,3 @@
+pub fn shared() -> bool {
+    true
+}
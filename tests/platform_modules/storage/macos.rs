@@ -0,0 +1,3 @@
+pub fn platform() -> &'static str {
+    "macos"
+}
@@ -0,0 +1,7 @@
+pub struct Imp;
+
+impl super::PlatformInfo for Imp {
+    fn name() -> &'static str {
+        "macos"
+    }
+}